@@ -0,0 +1,72 @@
+use std::{env, fmt::Write, fs, path::Path};
+
+#[path = "src/ballistics/ammo.rs"]
+#[allow(dead_code)]
+mod ammo;
+#[path = "src/fixed.rs"]
+#[allow(dead_code)]
+mod fixed;
+#[path = "src/ballistics/solver.rs"]
+mod solver;
+
+/// Mirrors the module layout of the firmware so the shared files resolve
+/// their `crate::ballistics` imports.
+mod ballistics {
+    pub(crate) use super::solver;
+}
+
+fn main() {
+    // Relative to this script, `host-tests` runs it from its own directory.
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join(file!());
+    let root = script.parent().unwrap();
+    for file in [
+        "build.rs",
+        "src/ballistics/ammo.rs",
+        "src/ballistics/solver.rs",
+        "src/fixed.rs",
+    ] {
+        println!("cargo:rerun-if-changed={}", root.join(file).display());
+    }
+
+    let mut holdovers = Vec::new();
+    let mut flight_times = Vec::new();
+    for ammo in ammo::BUILTIN_AMMO.iter() {
+        for hop_strength in 0..=solver::MAX_HOP_STRENGTH {
+            let mut holdover_row = [0; solver::TABLE_LEN];
+            let mut flight_time_row = [0; solver::TABLE_LEN];
+            ammo.ballistic_profile(solver::STANDARD_AIR_DENSITY)
+                .trajectory_table(hop_strength, &mut holdover_row, &mut flight_time_row);
+            let comment = format!("{} hop {}", ammo.name, hop_strength);
+            holdovers.push((comment.clone(), holdover_row));
+            // The same rows as the tables integrated at runtime, so time of
+            // flight, windage and lead do not change with where they came from.
+            flight_times.push((comment, flight_time_row));
+        }
+    }
+
+    let mut output = String::new();
+    writeln!(output, "progmem! {{").unwrap();
+    write_table(&mut output, "HOLDOVER_TABLES", &holdovers);
+    write_table(&mut output, "FLIGHT_TIME_TABLES", &flight_times);
+    writeln!(output, "}}").unwrap();
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("holdover_tables.rs");
+    fs::write(path, output).unwrap();
+}
+
+fn write_table(output: &mut String, name: &str, rows: &[(String, [i16; solver::TABLE_LEN])]) {
+    writeln!(
+        output,
+        "    static progmem {name}: [[i16; TABLE_LEN]; {}] = [",
+        rows.len()
+    )
+    .unwrap();
+    for (comment, row) in rows {
+        write!(output, "        // {comment}\n        [").unwrap();
+        for entry in row {
+            write!(output, "{entry}, ").unwrap();
+        }
+        output.push_str("],\n");
+    }
+    writeln!(output, "    ];").unwrap();
+}
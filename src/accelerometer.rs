@@ -0,0 +1,145 @@
+use embedded_hal::i2c::I2c;
+
+use crate::fixed::Fixed;
+
+/// Acceleration along the sight axes in milli-g. X points along the bore,
+/// Y to the right and Z up, so a level sight reads roughly `(0, 0, 1000)`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Acceleration {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl Acceleration {
+    /// Angle of the bore above the horizon in whole degrees, negative when
+    /// shooting downhill.
+    pub fn pitch_deg(&self) -> i8 {
+        let (x, y, z) = (
+            Fixed::from(self.x),
+            Fixed::from(self.y),
+            Fixed::from(self.z),
+        );
+        // Scale down first so the squares stay within Q16.16.
+        let level = ((y / 1000) * (y / 1000) + (z / 1000) * (z / 1000)).sqrt();
+        to_degrees((x / 1000).atan2(level))
+    }
+
+    /// Rotation of the sight around the bore in whole degrees, positive when
+    /// tilted to the right.
+    pub fn roll_deg(&self) -> i8 {
+        to_degrees(Fixed::from(self.y).atan2(Fixed::from(self.z)))
+    }
+}
+
+fn to_degrees(radians: Fixed) -> i8 {
+    (radians * 180 / Fixed::PI)
+        .round()
+        .clamp(i8::MIN as i32, i8::MAX as i32) as i8
+}
+
+/// Something that can measure which way gravity points.
+pub trait Accelerometer {
+    type Error;
+
+    fn read(&mut self) -> Result<Acceleration, Self::Error>;
+}
+
+const MPU6050_ADDRESS: u8 = 0x68;
+const MPU6050_PWR_MGMT_1: u8 = 0x6B;
+const MPU6050_ACCEL_CONFIG: u8 = 0x1C;
+const MPU6050_ACCEL_XOUT_H: u8 = 0x3B;
+/// Raw counts per g in the +-2 g range.
+const MPU6050_COUNTS_PER_G: i32 = 16384;
+
+/// InvenSense MPU6050 on the I2C bus, mounted with its axes matching the
+/// sight axes.
+pub struct Mpu6050<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C: I2c> Mpu6050<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    /// Wakes the sensor up and selects the +-2 g range.
+    pub fn init(&mut self) -> Result<(), I2C::Error> {
+        self.i2c.write(MPU6050_ADDRESS, &[MPU6050_PWR_MGMT_1, 0])?;
+        self.i2c.write(MPU6050_ADDRESS, &[MPU6050_ACCEL_CONFIG, 0])
+    }
+}
+
+impl<I2C: I2c> Accelerometer for Mpu6050<I2C> {
+    type Error = I2C::Error;
+
+    fn read(&mut self) -> Result<Acceleration, Self::Error> {
+        let mut buffer = [0u8; 6];
+        self.i2c
+            .write_read(MPU6050_ADDRESS, &[MPU6050_ACCEL_XOUT_H], &mut buffer)?;
+        let axis = |index: usize| {
+            let raw = i16::from_be_bytes([buffer[index], buffer[index + 1]]) as i32;
+            (raw * 1000 / MPU6050_COUNTS_PER_G) as i16
+        };
+        Ok(Acceleration {
+            x: axis(0),
+            y: axis(2),
+            z: axis(4),
+        })
+    }
+}
+
+/// Returns a fixed reading, for exercising the maths on the host without a
+/// sensor.
+#[cfg(test)]
+pub struct MockAccelerometer {
+    pub reading: Acceleration,
+}
+
+#[cfg(test)]
+impl Accelerometer for MockAccelerometer {
+    type Error = core::convert::Infallible;
+
+    fn read(&mut self) -> Result<Acceleration, Self::Error> {
+        Ok(self.reading)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(x: i16, y: i16, z: i16) -> Acceleration {
+        let mut accelerometer = MockAccelerometer {
+            reading: Acceleration { x, y, z },
+        };
+        accelerometer.read().unwrap()
+    }
+
+    #[test]
+    fn level_sight_has_no_pitch_or_roll() {
+        let reading = read(0, 0, 1000);
+        assert_eq!(reading.pitch_deg(), 0);
+        assert_eq!(reading.roll_deg(), 0);
+    }
+
+    #[test]
+    fn pitch_follows_the_bore() {
+        // sin 30 and cos 30 in milli-g.
+        assert_eq!(read(500, 0, 866).pitch_deg(), 30);
+        assert_eq!(read(-500, 0, 866).pitch_deg(), -30);
+        assert_eq!(read(1000, 0, 0).pitch_deg(), 90);
+    }
+
+    #[test]
+    fn pitch_ignores_roll() {
+        // 30 degrees up, rolled 45 degrees to the right.
+        assert_eq!(read(500, 612, 612).pitch_deg(), 30);
+    }
+
+    #[test]
+    fn roll_is_positive_to_the_right() {
+        assert_eq!(read(0, 174, 985).roll_deg(), 10);
+        assert_eq!(read(0, -174, 985).roll_deg(), -10);
+    }
+}
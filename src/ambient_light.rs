@@ -0,0 +1,68 @@
+/// Smooths the readings of an ambient light sensor, a light dependent
+/// resistor or phototransistor pulling an ADC pin up as the light gets
+/// brighter.
+pub struct AmbientLightFilter {
+    /// Running average in sixteenths of an ADC count, `None` before the
+    /// first sample.
+    average: Option<u16>,
+}
+
+/// Largest reading of the 10-bit ADC.
+const ADC_MAX: u16 = 1023;
+/// Each new sample moves the average by this fraction of the difference,
+/// so a shadow passing over the sensor does not flicker the display.
+const SMOOTHING_DIVISOR: i32 = 8;
+
+impl AmbientLightFilter {
+    pub fn new() -> Self {
+        Self { average: None }
+    }
+
+    /// Feeds one ADC reading, returns the smoothed light level in percent
+    /// of the sensor's range.
+    pub fn push(&mut self, sample: u16) -> u8 {
+        let sample = sample.min(ADC_MAX) as i32 * 16;
+        let average = match self.average {
+            None => sample,
+            Some(average) => {
+                let average = average as i32;
+                average + (sample - average) / SMOOTHING_DIVISOR
+            }
+        };
+        self.average = Some(average as u16);
+        (average * 100 / (ADC_MAX as i32 * 16)) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_the_first_sample() {
+        let mut filter = AmbientLightFilter::new();
+        assert_eq!(filter.push(ADC_MAX / 2), 49);
+    }
+
+    #[test]
+    fn converges_on_a_step() {
+        let mut filter = AmbientLightFilter::new();
+        filter.push(0);
+        // A single bright sample moves it only part of the way.
+        assert_eq!(filter.push(ADC_MAX), 12);
+        let mut last = 12;
+        for _ in 0..100 {
+            let light = filter.push(ADC_MAX);
+            assert!(light >= last);
+            last = light;
+        }
+        assert!(last >= 99, "stuck at {last} %");
+    }
+
+    #[test]
+    fn clamps_readings_past_the_adc_range() {
+        let mut filter = AmbientLightFilter::new();
+        assert_eq!(filter.push(u16::MAX), 100);
+        assert_eq!(filter.push(2000), 100);
+    }
+}
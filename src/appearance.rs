@@ -0,0 +1,200 @@
+use embedded_graphics::{pixelcolor::Rgb565, prelude::RgbColor};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ReticleColor {
+    Red,
+    Green,
+    Amber,
+    White,
+    /// Dim green for night vision devices, only used by the night vision
+    /// mode and not offered in the settings.
+    NightVision,
+}
+
+pub const RETICLE_COLORS: [ReticleColor; 4] = [
+    ReticleColor::Red,
+    ReticleColor::Green,
+    ReticleColor::Amber,
+    ReticleColor::White,
+];
+
+/// Labels of `RETICLE_COLORS`, for choosing one in the settings.
+pub const RETICLE_COLOR_NAMES: [&str; RETICLE_COLORS.len()] = ["Red", "Green", "Amber", "White"];
+
+impl ReticleColor {
+    pub fn rgb(self) -> Rgb565 {
+        match self {
+            ReticleColor::Red => Rgb565::RED,
+            ReticleColor::Green => Rgb565::GREEN,
+            ReticleColor::Amber => Rgb565::new(31, 40, 0),
+            ReticleColor::White => Rgb565::WHITE,
+            ReticleColor::NightVision => Rgb565::new(0, 12, 0),
+        }
+    }
+}
+
+pub const MIN_BRIGHTNESS: u8 = 1;
+pub const MAX_BRIGHTNESS: u8 = 5;
+/// Below `MIN_BRIGHTNESS`, the panel's minimum current for night vision
+/// devices. Only the night vision mode uses it.
+pub const NIGHT_VISION_BRIGHTNESS: u8 = 0;
+
+/// Light level in percent the ambient light has to move past a step
+/// boundary before the brightness follows.
+const LIGHT_HYSTERESIS: u8 = 3;
+
+/// What sets the panel brightness.
+#[derive(PartialEq, Clone, Copy)]
+pub enum BrightnessSource {
+    /// The brightness chosen in the settings.
+    Manual,
+    /// Follows the ambient light sensor along the `LightCurve`.
+    Sensor,
+}
+
+/// Maps the ambient light level in percent to a brightness step. Light at or
+/// below `dark` gives the lowest step, at or above `bright` the highest, and
+/// the steps are spread evenly in between.
+#[derive(PartialEq, Clone, Copy)]
+pub struct LightCurve {
+    pub dark: u8,
+    pub bright: u8,
+}
+
+impl LightCurve {
+    pub fn brightness(&self, light: u8) -> u8 {
+        if light <= self.dark {
+            return MIN_BRIGHTNESS;
+        }
+        if light >= self.bright {
+            return MAX_BRIGHTNESS;
+        }
+        let steps = (MAX_BRIGHTNESS - MIN_BRIGHTNESS + 1) as u16;
+        let step = (light - self.dark) as u16 * steps / (self.bright - self.dark) as u16;
+        (MIN_BRIGHTNESS + step as u8).min(MAX_BRIGHTNESS)
+    }
+
+    /// Brightness for `light` starting from `current`, only changing once
+    /// the light is clearly past the boundary of the current step.
+    pub fn brightness_from(&self, light: u8, current: u8) -> u8 {
+        // Steps for a little more and a little less light, it moves up only
+        // when even the lower one is above it, and down likewise.
+        let upper = self.brightness(light.saturating_add(LIGHT_HYSTERESIS));
+        let lower = self.brightness(light.saturating_sub(LIGHT_HYSTERESIS));
+        if lower > current {
+            lower
+        } else if upper < current {
+            upper
+        } else {
+            current
+        }
+    }
+}
+
+/// How the display looks, as opposed to what it shows.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Appearance {
+    pub reticle_color: ReticleColor,
+    /// Panel brightness step, from `MIN_BRIGHTNESS` to `MAX_BRIGHTNESS`,
+    /// when it is set manually.
+    pub brightness: u8,
+    pub brightness_source: BrightnessSource,
+    pub light_curve: LightCurve,
+    /// Reticle colour used instead of `reticle_color` while the sensor has
+    /// the panel at its lowest step.
+    pub dark_color: Option<ReticleColor>,
+}
+
+/// Bytes `Appearance::to_bytes` produces.
+pub const APPEARANCE_BYTES: usize = 6;
+
+/// Stands for `None` in the serialised `dark_color`.
+const NO_COLOR: u8 = 0xFF;
+
+impl Appearance {
+    pub const DEFAULT: Appearance = Appearance {
+        reticle_color: ReticleColor::Red,
+        brightness: MAX_BRIGHTNESS,
+        brightness_source: BrightnessSource::Manual,
+        light_curve: LightCurve {
+            dark: 5,
+            bright: 60,
+        },
+        dark_color: None,
+    };
+
+    /// The appearance as stored in the EEPROM.
+    pub fn to_bytes(self) -> [u8; APPEARANCE_BYTES] {
+        [
+            self.reticle_color as u8,
+            self.brightness,
+            self.brightness_source as u8,
+            self.light_curve.dark,
+            self.light_curve.bright,
+            self.dark_color.map_or(NO_COLOR, |color| color as u8),
+        ]
+    }
+
+    /// Appearance stored by `to_bytes`, if every setting in it is valid.
+    pub fn from_bytes(bytes: &[u8; APPEARANCE_BYTES]) -> Option<Self> {
+        let reticle_color = *RETICLE_COLORS.get(bytes[0] as usize)?;
+        let brightness = bytes[1];
+        if !(MIN_BRIGHTNESS..=MAX_BRIGHTNESS).contains(&brightness) {
+            return None;
+        }
+        let brightness_source = match bytes[2] {
+            0 => BrightnessSource::Manual,
+            1 => BrightnessSource::Sensor,
+            _ => return None,
+        };
+        let light_curve = LightCurve {
+            dark: bytes[3],
+            bright: bytes[4],
+        };
+        if light_curve.dark >= light_curve.bright || light_curve.bright > 100 {
+            return None;
+        }
+        let dark_color = match bytes[5] {
+            NO_COLOR => None,
+            index => Some(*RETICLE_COLORS.get(index as usize)?),
+        };
+        Some(Appearance {
+            reticle_color,
+            brightness,
+            brightness_source,
+            light_curve,
+            dark_color,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVE: LightCurve = LightCurve {
+        dark: 10,
+        bright: 60,
+    };
+
+    #[test]
+    fn spreads_the_steps_between_dark_and_bright() {
+        assert_eq!(CURVE.brightness(0), MIN_BRIGHTNESS);
+        assert_eq!(CURVE.brightness(10), MIN_BRIGHTNESS);
+        assert_eq!(CURVE.brightness(60), MAX_BRIGHTNESS);
+        assert_eq!(CURVE.brightness(100), MAX_BRIGHTNESS);
+        let steps: Vec<_> = (10..=60).map(|light| CURVE.brightness(light)).collect();
+        assert!(steps.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn holds_the_step_near_a_boundary() {
+        // 20 % is the boundary between the first two steps.
+        assert_eq!(CURVE.brightness(19), 1);
+        assert_eq!(CURVE.brightness(20), 2);
+        assert_eq!(CURVE.brightness_from(21, 1), 1);
+        assert_eq!(CURVE.brightness_from(19, 2), 2);
+        assert_eq!(CURVE.brightness_from(20 + LIGHT_HYSTERESIS, 1), 2);
+        assert_eq!(CURVE.brightness_from(19 - LIGHT_HYSTERESIS, 2), 1);
+    }
+}
@@ -0,0 +1,167 @@
+pub mod ammo;
+pub mod atmosphere;
+mod drop_table;
+mod holdover_table;
+pub mod incline;
+mod interpolation;
+pub mod lead;
+mod solver;
+pub mod stadia;
+mod wind;
+
+pub use drop_table::{DropTable, DROP_TABLE_BYTES, MAX_IMPACT_CM};
+pub use holdover_table::HoldoverTable;
+pub use solver::MAX_HOP_STRENGTH;
+pub use wind::Wind;
+
+use crate::fixed::Fixed;
+
+/// Limits of the calibrated scale, in hundredths of a pixel per milliradian.
+pub const MIN_PIXELS_PER_MRAD: u16 = 10;
+pub const MAX_PIXELS_PER_MRAD: u16 = 2000;
+
+/// How many display pixels one milliradian covers behind the lens. It
+/// depends on the lens and how far it sits from the display, so each sight
+/// is calibrated against a `CalibrationTarget`. Every angle shown on the
+/// display is converted through it.
+#[derive(PartialEq, Clone, Copy)]
+pub struct PixelScale {
+    /// Pixels per milliradian in hundredths.
+    pub pixels_per_mrad: u16,
+}
+
+/// Bytes `PixelScale::to_bytes` produces.
+pub const PIXEL_SCALE_BYTES: usize = 2;
+
+impl PixelScale {
+    pub const DEFAULT: PixelScale = PixelScale {
+        pixels_per_mrad: 50,
+    };
+
+    /// Scale at which `target` spans `spacing_px` pixels.
+    pub fn calibrated(target: CalibrationTarget, spacing_px: i16) -> Self {
+        // pixels / (cm * 10 / m) in hundredths.
+        let pixels_per_mrad =
+            spacing_px as i32 * 10 * target.distance_m as i32 / target.spacing_cm.max(1) as i32;
+        PixelScale {
+            pixels_per_mrad: pixels_per_mrad
+                .clamp(MIN_PIXELS_PER_MRAD as i32, MAX_PIXELS_PER_MRAD as i32)
+                as u16,
+        }
+    }
+
+    /// Smallest angle in hundredths of a milliradian that visibly moves the
+    /// reticle, a quarter of a pixel.
+    pub fn min_adjustment(&self) -> i16 {
+        let pixels_per_mrad = self.pixels_per_mrad.max(1) as i32;
+        ((2500 + pixels_per_mrad - 1) / pixels_per_mrad) as i16
+    }
+
+    fn per_mrad(&self) -> Fixed {
+        Fixed::from_ratio(self.pixels_per_mrad as i32, 100)
+    }
+
+    /// Pixels covering `mrad_hundredths`, including the fraction of a
+    /// pixel.
+    pub fn offset(&self, mrad_hundredths: i16) -> Fixed {
+        Fixed::from(mrad_hundredths) / 100 * self.per_mrad()
+    }
+
+    /// Whole pixels covering `mrad_hundredths`.
+    pub fn pixels(&self, mrad_hundredths: i16) -> i16 {
+        self.offset(mrad_hundredths)
+            .round()
+            .clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+
+    /// Angle `pixels` cover, in milliradians.
+    pub fn mrad(&self, pixels: i16) -> Fixed {
+        Fixed::from(pixels) / self.per_mrad()
+    }
+
+    /// The scale as stored in the EEPROM, little-endian.
+    pub fn to_bytes(self) -> [u8; PIXEL_SCALE_BYTES] {
+        self.pixels_per_mrad.to_le_bytes()
+    }
+
+    /// Scale stored by `to_bytes`, if it is within the calibration limits.
+    pub fn from_bytes(bytes: &[u8; PIXEL_SCALE_BYTES]) -> Option<Self> {
+        let pixels_per_mrad = u16::from_le_bytes(*bytes);
+        (MIN_PIXELS_PER_MRAD..=MAX_PIXELS_PER_MRAD)
+            .contains(&pixels_per_mrad)
+            .then_some(PixelScale { pixels_per_mrad })
+    }
+}
+
+/// Two marks a known distance apart, looked at from a known range, for
+/// calibrating the `PixelScale`. Ten centimetres at ten metres span ten
+/// milliradians.
+#[derive(PartialEq, Clone, Copy)]
+pub struct CalibrationTarget {
+    pub spacing_cm: u8,
+    pub distance_m: u8,
+}
+
+/// Correction along one axis needed to hit at a given range.
+///
+/// For vertical holdovers positive values mean the BB lands below the line of
+/// sight, so the aim point has to move down. For windage positive values mean
+/// the BB lands right of it, so the aim point moves right.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Holdover {
+    /// Holdover in tenths of a milliradian.
+    pub mrad_tenths: i16,
+}
+
+impl Holdover {
+    pub const NONE: Holdover = Holdover { mrad_tenths: 0 };
+
+    pub fn from_mrad_tenths(mrad_tenths: i16) -> Self {
+        Holdover { mrad_tenths }
+    }
+
+    /// Offset of the aim point on the display in whole pixels.
+    pub fn pixels(&self, scale: PixelScale) -> i16 {
+        scale.pixels(self.mrad_tenths.saturating_mul(10))
+    }
+
+    /// Offset of the aim point on the display, including the fraction of a
+    /// pixel.
+    pub fn offset(&self, scale: PixelScale) -> Fixed {
+        scale.offset(self.mrad_tenths.saturating_mul(10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_converts_between_pixels_and_angles() {
+        // Ten centimetres at ten metres span ten milliradians.
+        let target = CalibrationTarget {
+            spacing_cm: 10,
+            distance_m: 10,
+        };
+        let scale = PixelScale::calibrated(target, 50);
+        assert_eq!(scale.pixels_per_mrad, 500);
+        assert_eq!(scale.mrad(50), Fixed::from_int(10));
+        assert_eq!(scale.pixels(1000), 50);
+        let holdover = Holdover::from_mrad_tenths(5);
+        assert_eq!(holdover.offset(scale), Fixed::from_ratio(5, 2));
+    }
+
+    #[test]
+    fn min_adjustment_moves_a_quarter_pixel() {
+        for pixels_per_mrad in [MIN_PIXELS_PER_MRAD, 50, 333, MAX_PIXELS_PER_MRAD] {
+            let scale = PixelScale { pixels_per_mrad };
+            // A quarter pixel in hundredths of a milliradian times hundredths
+            // of a pixel per milliradian.
+            let adjustment = scale.min_adjustment() as i32;
+            assert!(adjustment * pixels_per_mrad as i32 >= 2500);
+            assert!((adjustment - 1) * (pixels_per_mrad as i32) < 2500);
+        }
+        // Half a milliradian at the default 0.5 pixels per milliradian.
+        assert_eq!(PixelScale::DEFAULT.min_adjustment(), 50);
+    }
+}
@@ -0,0 +1,139 @@
+// This file is also compiled into `build.rs` to generate the holdover tables,
+// so it must only depend on `core`, `crate::fixed` and `crate::ballistics::solver`.
+
+use crate::{
+    ballistics::solver::{BallisticProfile, SIGHT_HEIGHT_MM},
+    fixed::Fixed,
+};
+
+/// A type of BB together with the velocity the replica fires it at.
+#[derive(Clone, Copy)]
+pub struct AmmoProfile {
+    pub name: &'static str,
+    /// BB mass in grams.
+    pub mass_g: Fixed,
+    /// BB diameter in millimetres.
+    pub diameter_mm: Fixed,
+    /// Muzzle velocity in m/s.
+    pub muzzle_velocity: Fixed,
+}
+
+// Only what the BB does counts, so a user profile that matches a built-in
+// one still gets its precomputed holdover table.
+impl PartialEq for AmmoProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.mass_g == other.mass_g
+            && self.diameter_mm == other.diameter_mm
+            && self.muzzle_velocity == other.muzzle_velocity
+    }
+}
+
+/// Standard 6 mm BBs, with velocities for a replica shooting about 1 J.
+pub const BUILTIN_AMMO: [AmmoProfile; 3] = [
+    AmmoProfile {
+        name: "0.20g",
+        mass_g: Fixed::from_ratio(20, 100),
+        diameter_mm: Fixed::from_ratio(595, 100),
+        muzzle_velocity: Fixed::from_int(100),
+    },
+    AmmoProfile {
+        name: "0.25g",
+        mass_g: Fixed::from_ratio(25, 100),
+        diameter_mm: Fixed::from_ratio(595, 100),
+        muzzle_velocity: Fixed::from_int(89),
+    },
+    AmmoProfile {
+        name: "0.32g",
+        mass_g: Fixed::from_ratio(32, 100),
+        diameter_mm: Fixed::from_ratio(595, 100),
+        muzzle_velocity: Fixed::from_int(79),
+    },
+];
+
+/// Number of user-defined profiles that follow the built-in ones.
+pub const USER_AMMO_SLOTS: usize = 2;
+/// Limits of the BB mass of a user profile in hundredths of a gram.
+pub const MIN_USER_MASS_CG: u8 = 12;
+pub const MAX_USER_MASS_CG: u8 = 48;
+/// Limits of the BB diameter of a user profile in hundredths of a
+/// millimetre, 6 mm BBs measure 5.93 to 5.97 mm.
+pub const MIN_USER_DIAMETER: u16 = 590;
+pub const MAX_USER_DIAMETER: u16 = 600;
+/// Bytes `AmmoProfile::to_bytes` produces.
+pub const USER_AMMO_BYTES: usize = 3;
+
+/// User-defined profiles start out as copies of the 0.25 g built-in.
+pub const DEFAULT_USER_AMMO: [AmmoProfile; USER_AMMO_SLOTS] = [
+    AmmoProfile {
+        name: "User 1",
+        ..BUILTIN_AMMO[1]
+    },
+    AmmoProfile {
+        name: "User 2",
+        ..BUILTIN_AMMO[1]
+    },
+];
+
+/// Labels of every selectable profile, built-in ones first.
+pub const AMMO_NAMES: [&str; BUILTIN_AMMO.len() + USER_AMMO_SLOTS] = [
+    BUILTIN_AMMO[0].name,
+    BUILTIN_AMMO[1].name,
+    BUILTIN_AMMO[2].name,
+    DEFAULT_USER_AMMO[0].name,
+    DEFAULT_USER_AMMO[1].name,
+];
+
+impl AmmoProfile {
+    /// Ballistic properties of this profile flying through air of
+    /// `air_density` kg/m^3.
+    pub fn ballistic_profile(&self, air_density: Fixed) -> BallisticProfile {
+        BallisticProfile {
+            mass_g: self.mass_g,
+            diameter_mm: self.diameter_mm,
+            muzzle_velocity: self.muzzle_velocity,
+            sight_height_mm: SIGHT_HEIGHT_MM,
+            air_density,
+        }
+    }
+
+    /// Mass and diameter of a user profile as stored in the EEPROM.
+    pub fn to_bytes(self) -> [u8; USER_AMMO_BYTES] {
+        let mass_cg = (self.mass_g * 100).round() as u8;
+        let [low, high] = ((self.diameter_mm * 100).round() as u16).to_le_bytes();
+        [mass_cg, low, high]
+    }
+
+    /// `self` with the mass and diameter stored by `to_bytes`, if both are
+    /// within the user profile limits.
+    pub fn with_bytes(self, bytes: &[u8; USER_AMMO_BYTES]) -> Option<Self> {
+        let mass_cg = bytes[0];
+        let diameter = u16::from_le_bytes([bytes[1], bytes[2]]);
+        ((MIN_USER_MASS_CG..=MAX_USER_MASS_CG).contains(&mass_cg)
+            && (MIN_USER_DIAMETER..=MAX_USER_DIAMETER).contains(&diameter))
+        .then_some(AmmoProfile {
+            mass_g: Fixed::from_ratio(mass_cg as i32, 100),
+            diameter_mm: Fixed::from_ratio(diameter as i32, 100),
+            ..self
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_compare_by_what_the_bb_does() {
+        assert!(DEFAULT_USER_AMMO[0] == BUILTIN_AMMO[1]);
+        let heavier = AmmoProfile {
+            mass_g: Fixed::from_ratio(28, 100),
+            ..DEFAULT_USER_AMMO[0]
+        };
+        assert!(heavier != BUILTIN_AMMO[1]);
+        let faster = AmmoProfile {
+            muzzle_velocity: Fixed::from_int(95),
+            ..DEFAULT_USER_AMMO[0]
+        };
+        assert!(faster != BUILTIN_AMMO[1]);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{ballistics::solver::STANDARD_AIR_DENSITY, fixed::Fixed};
+
+/// Specific gas constant of dry air in J/(kg K).
+const GAS_CONSTANT: Fixed = Fixed::from_f32(287.05);
+/// Height in metres over which the pressure drops by a factor of e.
+const SCALE_HEIGHT_M: i32 = 8434;
+const ZERO_CELSIUS_K: Fixed = Fixed::from_f32(273.15);
+/// Densities are rounded to this fraction of a kg/m^3, so sensor noise does
+/// not keep recomputing the holdover table.
+const DENSITY_STEPS_PER_UNIT: i32 = 200;
+
+/// Limits of the pressure in hPa, from a sea level storm to the station
+/// pressure high in the mountains.
+pub const MIN_PRESSURE_HPA: u16 = 600;
+pub const MAX_PRESSURE_HPA: u16 = 1100;
+
+/// Where the air measurements come from.
+#[derive(PartialEq, Clone, Copy)]
+pub enum AtmosphereSource {
+    /// Entered by the user, with the pressure reduced to sea level as in
+    /// weather reports.
+    Manual,
+    /// Read from the barometer, which measures the pressure where the sight
+    /// is, so the altitude is not needed.
+    Sensor,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct Atmosphere {
+    pub temperature_c: i8,
+    /// Barometric pressure in hPa.
+    pub pressure_hpa: u16,
+    /// Altitude above sea level in metres.
+    pub altitude_m: u16,
+    pub source: AtmosphereSource,
+}
+
+impl Atmosphere {
+    /// The standard atmosphere at sea level.
+    pub const STANDARD: Atmosphere = Atmosphere {
+        temperature_c: 15,
+        pressure_hpa: 1013,
+        altitude_m: 0,
+        source: AtmosphereSource::Manual,
+    };
+
+    /// Pressure at the shooting position in hPa.
+    pub fn station_pressure_hpa(&self) -> Fixed {
+        let pressure = Fixed::from(self.pressure_hpa as i16);
+        match self.source {
+            AtmosphereSource::Sensor => pressure,
+            AtmosphereSource::Manual => pressure * self.altitude_decay(),
+        }
+    }
+
+    /// Switches to `source`, converting the pressure between the sea level
+    /// and station values so the density stays the same until the sensor is
+    /// read or the user changes it.
+    pub fn set_source(&mut self, source: AtmosphereSource) {
+        let pressure = Fixed::from(self.pressure_hpa as i16);
+        let pressure = match (self.source, source) {
+            (AtmosphereSource::Sensor, AtmosphereSource::Manual) => {
+                pressure / self.altitude_decay()
+            }
+            (AtmosphereSource::Manual, AtmosphereSource::Sensor) => {
+                pressure * self.altitude_decay()
+            }
+            _ => pressure,
+        };
+        self.pressure_hpa = pressure.round().clamp(0, u16::MAX as i32) as u16;
+        self.source = source;
+    }
+
+    /// Fraction of the sea level pressure left at `altitude_m`,
+    /// exp(-altitude / scale height) from the first terms of its series,
+    /// good to 0.1% up to 3000 m.
+    fn altitude_decay(&self) -> Fixed {
+        let x = Fixed::from_ratio(self.altitude_m as i32, SCALE_HEIGHT_M);
+        Fixed::ONE - x * (Fixed::ONE - x / 2 * (Fixed::ONE - x / 3 * (Fixed::ONE - x / 4)))
+    }
+
+    /// Air density in kg/m^3 from the ideal gas law, ignoring humidity.
+    pub fn density(&self) -> Fixed {
+        let kelvin = Fixed::from(self.temperature_c as i16) + ZERO_CELSIUS_K;
+        // hPa to Pa after dividing, 100 kPa would not fit Q16.16.
+        let density = self.station_pressure_hpa() / kelvin * 100 / GAS_CONSTANT;
+        if density <= Fixed::ZERO {
+            return STANDARD_AIR_DENSITY;
+        }
+        Fixed::from_ratio(
+            (density * DENSITY_STEPS_PER_UNIT).round(),
+            DENSITY_STEPS_PER_UNIT,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barometer::{AirReading, Barometer, MockBarometer};
+
+    fn assert_density(atmosphere: Atmosphere, expected: f64) {
+        let density = atmosphere.density().raw() as f64 / Fixed::ONE.raw() as f64;
+        assert!(
+            (density - expected).abs() <= 0.005,
+            "{density} kg/m^3, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn standard_atmosphere_has_standard_density() {
+        assert!(Atmosphere::STANDARD.density() == STANDARD_AIR_DENSITY);
+    }
+
+    #[test]
+    fn density_follows_the_ideal_gas_law() {
+        let hot = Atmosphere {
+            temperature_c: 35,
+            ..Atmosphere::STANDARD
+        };
+        assert_density(hot, 1.146);
+        let cold = Atmosphere {
+            temperature_c: -10,
+            pressure_hpa: 1030,
+            ..Atmosphere::STANDARD
+        };
+        assert_density(cold, 1.364);
+    }
+
+    #[test]
+    fn manual_pressure_is_reduced_to_the_altitude() {
+        let mountain = Atmosphere {
+            temperature_c: 0,
+            altitude_m: 2000,
+            ..Atmosphere::STANDARD
+        };
+        // 1013 hPa at sea level is about 799 hPa at 2000 m.
+        assert!((mountain.station_pressure_hpa().round() - 799).abs() <= 1);
+        assert_density(mountain, 1.019);
+    }
+
+    #[test]
+    fn sensor_pressure_is_taken_as_is() {
+        let mut barometer = MockBarometer {
+            reading: AirReading {
+                temperature_c: 0,
+                pressure_hpa: 799,
+            },
+        };
+        let reading = barometer.read().unwrap();
+        let atmosphere = Atmosphere {
+            temperature_c: reading.temperature_c,
+            pressure_hpa: reading.pressure_hpa,
+            altitude_m: 2000,
+            source: AtmosphereSource::Sensor,
+        };
+        assert!(atmosphere.station_pressure_hpa().round() == 799);
+        assert_density(atmosphere, 1.019);
+    }
+
+    #[test]
+    fn switching_source_keeps_the_density() {
+        let sensor = Atmosphere {
+            temperature_c: 5,
+            pressure_hpa: 900,
+            altitude_m: 1000,
+            source: AtmosphereSource::Sensor,
+        };
+        let mut manual = sensor;
+        manual.set_source(AtmosphereSource::Manual);
+        // About 1013 hPa at sea level.
+        assert!((manual.pressure_hpa as i32 - 1013).abs() <= 1);
+        assert!((manual.density() - sensor.density()).abs() <= Fixed::from_ratio(1, 200));
+        let mut back = manual;
+        back.set_source(AtmosphereSource::Sensor);
+        assert!((back.pressure_hpa as i32 - 900).abs() <= 1);
+        let mut unchanged = sensor;
+        unchanged.set_source(AtmosphereSource::Sensor);
+        assert!(unchanged == sensor);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::ballistics::{interpolation, Holdover};
+
+/// Distance in metres between two measured rows, the first one is at this
+/// range too.
+pub const DROP_TABLE_STEP: u8 = 10;
+/// Number of measured rows, covering 10 to 50 m.
+pub const DROP_TABLE_ROWS: usize = 5;
+/// Largest impact offset a row can hold in centimetres, either way.
+pub const MAX_IMPACT_CM: i16 = 300;
+/// Bytes `DropTable::to_bytes` produces.
+pub const DROP_TABLE_BYTES: usize = 2 * DROP_TABLE_ROWS;
+
+/// Impact offsets measured on the range, used instead of the ballistic model.
+#[derive(PartialEq, Clone, Copy)]
+pub struct DropTable {
+    /// How many centimetres below the point of aim the BB lands at each row.
+    pub impacts_cm: [i16; DROP_TABLE_ROWS],
+}
+
+impl DropTable {
+    pub const EMPTY: DropTable = DropTable {
+        impacts_cm: [0; DROP_TABLE_ROWS],
+    };
+
+    /// Holdover at `range` metres, interpolated between the measured rows and
+    /// extrapolated along the last two past them.
+    pub fn holdover(&self, range: u8) -> Holdover {
+        if range == 0 {
+            return Holdover::NONE;
+        }
+        // The muzzle is the implicit first row with no offset.
+        let mut rows = [0; DROP_TABLE_ROWS + 1];
+        rows[1..].copy_from_slice(&self.impacts_cm);
+        // Impacts are kept times the row spacing so extrapolating past the
+        // rows does not round to whole centimetres.
+        let step = DROP_TABLE_STEP as i32;
+        let scaled_impact = match interpolation::linear(&rows, DROP_TABLE_STEP, range) {
+            Some(impact_cm) => impact_cm as i32 * step,
+            None => {
+                let last = rows[DROP_TABLE_ROWS] as i32;
+                let slope = last - rows[DROP_TABLE_ROWS - 1] as i32;
+                let past = range as i32 - DROP_TABLE_ROWS as i32 * step;
+                last * step + slope * past
+            }
+        };
+        // 1 cm at 1 m is 10 mrad, so 100 tenths.
+        let mrad_tenths = scaled_impact * 100 / (range as i32 * step);
+        Holdover::from_mrad_tenths(mrad_tenths.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+    }
+
+    /// The impacts as stored in the EEPROM, little-endian.
+    pub fn to_bytes(self) -> [u8; DROP_TABLE_BYTES] {
+        let mut bytes = [0; DROP_TABLE_BYTES];
+        for (chunk, impact_cm) in bytes.chunks_exact_mut(2).zip(self.impacts_cm) {
+            chunk.copy_from_slice(&impact_cm.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Table stored by `to_bytes`, if every impact is within `MAX_IMPACT_CM`.
+    pub fn from_bytes(bytes: &[u8; DROP_TABLE_BYTES]) -> Option<Self> {
+        let mut table = DropTable::EMPTY;
+        for (impact_cm, chunk) in table.impacts_cm.iter_mut().zip(bytes.chunks_exact(2)) {
+            *impact_cm = i16::from_le_bytes([chunk[0], chunk[1]]);
+            if impact_cm.abs() > MAX_IMPACT_CM {
+                return None;
+            }
+        }
+        Some(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: DropTable = DropTable {
+        impacts_cm: [2, 8, 18, 32, 50],
+    };
+
+    fn mrad_tenths(range: u8) -> i16 {
+        TABLE.holdover(range).mrad_tenths
+    }
+
+    #[test]
+    fn starts_at_the_muzzle() {
+        assert_eq!(mrad_tenths(0), 0);
+        // Half way to the first row, half its impact.
+        assert_eq!(mrad_tenths(5), 20);
+    }
+
+    #[test]
+    fn matches_the_measured_rows() {
+        for (row, impact_cm) in TABLE.impacts_cm.iter().enumerate() {
+            let range = (row as u8 + 1) * DROP_TABLE_STEP;
+            assert_eq!(mrad_tenths(range), impact_cm * 100 / range as i16);
+        }
+    }
+
+    #[test]
+    fn interpolates_between_rows() {
+        // 13 cm at 25 m.
+        assert_eq!(mrad_tenths(25), 52);
+        // 41 cm at 45 m.
+        assert_eq!(mrad_tenths(45), 91);
+    }
+
+    #[test]
+    fn keeps_growing_past_the_last_row() {
+        // 18 cm a row further on, so 68 cm at 60 m and 122 cm at 90 m.
+        assert_eq!(mrad_tenths(60), 113);
+        assert_eq!(mrad_tenths(90), 135);
+        let mut last = mrad_tenths(50);
+        for range in 51..=u8::MAX {
+            let holdover = mrad_tenths(range);
+            assert!(holdover >= last, "holdover shrinks at {range} m");
+            last = holdover;
+        }
+    }
+}
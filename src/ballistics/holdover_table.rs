@@ -0,0 +1,198 @@
+#[cfg(not(target_arch = "avr"))]
+use crate::progmem::progmem;
+#[cfg(target_arch = "avr")]
+use avr_progmem::progmem;
+
+use crate::ballistics::{
+    ammo::{AmmoProfile, BUILTIN_AMMO},
+    interpolation,
+    solver::{MAX_HOP_STRENGTH, OUT_OF_REACH, STANDARD_AIR_DENSITY, TABLE_LEN, TABLE_STEP},
+    Holdover,
+};
+use crate::fixed::Fixed;
+
+// Defines `HOLDOVER_TABLES`, one row of holdovers (in tenths of a
+// milliradian) and `FLIGHT_TIME_TABLES`, one row of flight times (in
+// milliseconds), per built-in ammo profile and hop strength.
+include!(concat!(env!("OUT_DIR"), "/holdover_tables.rs"));
+
+/// Holdovers and flight times for one ammo profile, hop strength and air
+/// density, every `TABLE_STEP` metres.
+#[derive(PartialEq, Clone, Copy)]
+pub struct HoldoverTable {
+    ammo: AmmoProfile,
+    hop_strength: u8,
+    air_density: Fixed,
+    holdovers: [i16; TABLE_LEN],
+    flight_times: [i16; TABLE_LEN],
+}
+
+impl HoldoverTable {
+    /// Loads the precomputed table for built-in profiles in standard air,
+    /// anything else is integrated on the spot.
+    pub fn new(ammo: &AmmoProfile, hop_strength: u8, air_density: Fixed) -> Self {
+        let hop_strength = hop_strength.min(MAX_HOP_STRENGTH);
+        let builtin = BUILTIN_AMMO.iter().position(|builtin| builtin == ammo);
+        let (holdovers, flight_times) =
+            match builtin.filter(|_| air_density == STANDARD_AIR_DENSITY) {
+                Some(index) => {
+                    let row = index * (MAX_HOP_STRENGTH as usize + 1) + hop_strength as usize;
+                    (
+                        HOLDOVER_TABLES.load_at(row),
+                        FLIGHT_TIME_TABLES.load_at(row),
+                    )
+                }
+                None => {
+                    let mut holdovers = [0; TABLE_LEN];
+                    let mut flight_times = [0; TABLE_LEN];
+                    ammo.ballistic_profile(air_density).trajectory_table(
+                        hop_strength,
+                        &mut holdovers,
+                        &mut flight_times,
+                    );
+                    (holdovers, flight_times)
+                }
+            };
+        Self {
+            ammo: *ammo,
+            hop_strength,
+            air_density,
+            holdovers,
+            flight_times,
+        }
+    }
+
+    /// Whether the table was computed for these inputs.
+    pub fn is_for(&self, ammo: &AmmoProfile, hop_strength: u8, air_density: Fixed) -> bool {
+        self.ammo == *ammo
+            && self.hop_strength == hop_strength.min(MAX_HOP_STRENGTH)
+            && self.air_density == air_density
+    }
+
+    /// Holdover at `range` metres, `None` where the BB does not get.
+    pub fn holdover(&self, range: u8) -> Option<Holdover> {
+        interpolation::linear(&self.holdovers, TABLE_STEP, range).map(Holdover::from_mrad_tenths)
+    }
+
+    /// Time of flight to `range` metres in milliseconds, `None` where the BB
+    /// does not get.
+    pub fn flight_time_ms(&self, range: u8) -> Option<i16> {
+        interpolation::linear(&self.flight_times, TABLE_STEP, range)
+    }
+
+    /// Average velocity in m/s over the table interval containing `range`,
+    /// taken from the flight times. `None` where the BB does not get and
+    /// past the end of the table.
+    pub fn velocity(&self, range: u8) -> Option<Fixed> {
+        let last = (TABLE_LEN - 1) as u8 * TABLE_STEP;
+        if range > last {
+            return None;
+        }
+        // The last entry closes the interval before it.
+        let index = (range.min(last - 1) / TABLE_STEP) as usize;
+        let (start, end) = (self.flight_times[index], self.flight_times[index + 1]);
+        if end == OUT_OF_REACH || end <= start {
+            return None;
+        }
+        Some(Fixed::from(TABLE_STEP) * 1000 / (end - start) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Holdovers and flight times integrated at runtime.
+    fn integrated(ammo: &AmmoProfile, hop_strength: u8) -> ([i16; TABLE_LEN], [i16; TABLE_LEN]) {
+        let mut holdovers = [0; TABLE_LEN];
+        let mut flight_times = [0; TABLE_LEN];
+        ammo.ballistic_profile(STANDARD_AIR_DENSITY)
+            .trajectory_table(hop_strength, &mut holdovers, &mut flight_times);
+        (holdovers, flight_times)
+    }
+
+    #[test]
+    fn loads_the_precomputed_row_of_each_profile() {
+        for ammo in BUILTIN_AMMO.iter() {
+            for hop_strength in 0..=MAX_HOP_STRENGTH {
+                let table = HoldoverTable::new(ammo, hop_strength, STANDARD_AIR_DENSITY);
+                let (holdovers, flight_times) = integrated(ammo, hop_strength);
+                assert_eq!(
+                    table.holdovers, holdovers,
+                    "{} hop {hop_strength}",
+                    ammo.name
+                );
+                assert_eq!(
+                    table.flight_times, flight_times,
+                    "{} hop {hop_strength}",
+                    ammo.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interpolates_the_rows() {
+        let table = HoldoverTable::new(&BUILTIN_AMMO[1], 5, STANDARD_AIR_DENSITY);
+        let reached = table
+            .holdovers
+            .iter()
+            .take_while(|holdover| **holdover != OUT_OF_REACH);
+        for (index, holdover) in reached.enumerate() {
+            let range = index as u8 * TABLE_STEP;
+            assert_eq!(table.holdover(range).unwrap().mrad_tenths, *holdover);
+        }
+        let (low, high) = (table.holdovers[6] as i32, table.holdovers[7] as i32);
+        let between = table.holdover(6 * TABLE_STEP + 2).unwrap().mrad_tenths as i32;
+        assert!(low.min(high) <= between && between <= low.max(high));
+    }
+
+    #[test]
+    fn reports_ranges_past_the_reach_as_out_of_reach() {
+        let slow = AmmoProfile {
+            muzzle_velocity: Fixed::from_int(25),
+            ..BUILTIN_AMMO[0]
+        };
+        let table = HoldoverTable::new(&slow, 0, STANDARD_AIR_DENSITY);
+        let reach = table
+            .holdovers
+            .iter()
+            .position(|holdover| *holdover == OUT_OF_REACH)
+            .expect("a 25 m/s BB does not get to 80 m") as u8
+            - 1;
+        let last = reach * TABLE_STEP;
+        assert!(table.holdover(last).is_some());
+        assert!(table.flight_time_ms(last).is_some());
+        for range in last + 1..=last + TABLE_STEP {
+            assert_eq!(table.holdover(range), None, "{range} m");
+            assert_eq!(table.flight_time_ms(range), None, "{range} m");
+        }
+        assert_eq!(table.velocity(last + 1), None);
+    }
+
+    #[test]
+    fn has_no_velocity_past_the_table() {
+        let fast = AmmoProfile {
+            muzzle_velocity: Fixed::from_int(150),
+            ..BUILTIN_AMMO[2]
+        };
+        let table = HoldoverTable::new(&fast, 5, STANDARD_AIR_DENSITY);
+        let last = (TABLE_LEN - 1) as u8 * TABLE_STEP;
+        let at_last = table
+            .velocity(last)
+            .expect("a 150 m/s BB gets to the end of the table");
+        assert_eq!(table.velocity(last - 1), Some(at_last));
+        assert!(table.velocity(0).unwrap() > at_last);
+        assert_eq!(table.velocity(last + 1), None);
+        assert_eq!(table.velocity(u8::MAX), None);
+    }
+
+    #[test]
+    fn knows_what_it_was_computed_for() {
+        let table = HoldoverTable::new(&BUILTIN_AMMO[0], 3, STANDARD_AIR_DENSITY);
+        assert!(table.is_for(&BUILTIN_AMMO[0], 3, STANDARD_AIR_DENSITY));
+        assert!(!table.is_for(&BUILTIN_AMMO[1], 3, STANDARD_AIR_DENSITY));
+        assert!(!table.is_for(&BUILTIN_AMMO[0], 4, STANDARD_AIR_DENSITY));
+        assert!(!table.is_for(&BUILTIN_AMMO[0], 3, Fixed::ONE));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    ballistics::{solver::SIGHT_HEIGHT_MM, Holdover},
+    fixed::Fixed,
+};
+
+/// Applies the rifleman's rule: only the horizontal component of the range
+/// is affected by gravity, so the holdover for a shot `inclination_deg`
+/// degrees up or down scales with its cosine.
+pub fn compensate(holdover: Holdover, inclination_deg: i8) -> Holdover {
+    if inclination_deg == 0 {
+        return holdover;
+    }
+    let angle = Fixed::from(inclination_deg as i16) * Fixed::PI / 180;
+    let compensated = Fixed::from(holdover.mrad_tenths) * angle.cos();
+    Holdover::from_mrad_tenths(compensated.round() as i16)
+}
+
+/// Holdover at `range` metres for a shot `inclination_deg` degrees up or
+/// down, from the holdover of a level shot with the bore parallel to the
+/// line of sight. Only the drop under gravity is compensated, the sight
+/// still sits `SIGHT_HEIGHT_MM` above the bore.
+pub fn compensate_level(level: Holdover, range: u8, inclination_deg: i8) -> Holdover {
+    // 1 mm at 1 m is 1 mrad.
+    let sight_height = match range {
+        0 => 0,
+        range => (SIGHT_HEIGHT_MM * 10 / range as i32).round() as i16,
+    };
+    let drop = Holdover::from_mrad_tenths(level.mrad_tenths.saturating_sub(sight_height));
+    let drop = compensate(drop, inclination_deg);
+    Holdover::from_mrad_tenths(drop.mrad_tenths.saturating_add(sight_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accelerometer::{Acceleration, Accelerometer, MockAccelerometer};
+
+    fn inclination(x: i16, z: i16) -> i8 {
+        let mut accelerometer = MockAccelerometer {
+            reading: Acceleration { x, y: 0, z },
+        };
+        accelerometer.read().unwrap().pitch_deg()
+    }
+
+    #[test]
+    fn level_shots_are_unchanged() {
+        let holdover = Holdover::from_mrad_tenths(123);
+        assert_eq!(compensate(holdover, inclination(0, 1000)), holdover);
+    }
+
+    #[test]
+    fn scales_with_the_cosine_of_the_inclination() {
+        let holdover = Holdover::from_mrad_tenths(200);
+        // 60 degrees up and down.
+        for x in [866, -866] {
+            assert_eq!(
+                compensate(holdover, inclination(x, 500)),
+                Holdover::from_mrad_tenths(100)
+            );
+        }
+        assert_eq!(
+            compensate(holdover, inclination(1000, 0)),
+            Holdover::from_mrad_tenths(0)
+        );
+    }
+
+    #[test]
+    fn keeps_the_sight_height() {
+        // 40 mm at 20 m is 2 mrad, the other 10 mrad are the drop.
+        let level = Holdover::from_mrad_tenths(120);
+        assert_eq!(
+            compensate_level(level, 20, inclination(866, 500)),
+            Holdover::from_mrad_tenths(70)
+        );
+        assert_eq!(compensate_level(level, 20, 0), level);
+    }
+}
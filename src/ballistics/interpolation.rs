@@ -0,0 +1,56 @@
+use crate::ballistics::solver::OUT_OF_REACH;
+
+/// Piecewise-linear interpolation of `table`, whose entries are `step`
+/// metres apart and start at 0 m. `None` past the last entry, and next to
+/// entries that are `OUT_OF_REACH` instead of blending towards them.
+pub fn linear(table: &[i16], step: u8, range: u8) -> Option<i16> {
+    let index = (range / step) as usize;
+    let fraction = (range % step) as i32;
+    let low = *table.get(index)?;
+    // On an entry the next one does not matter, it may be past the end.
+    let high = match fraction {
+        0 => low,
+        _ => *table.get(index + 1)?,
+    };
+    if low == OUT_OF_REACH || high == OUT_OF_REACH {
+        return None;
+    }
+    let (low, high) = (low as i32, high as i32);
+    Some((low + (high - low) * fraction / step as i32) as i16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: [i16; 4] = [0, 10, 30, -20];
+
+    #[test]
+    fn returns_the_entries() {
+        for (index, entry) in TABLE.iter().enumerate() {
+            assert_eq!(linear(&TABLE, 5, index as u8 * 5), Some(*entry));
+        }
+    }
+
+    #[test]
+    fn interpolates_between_entries() {
+        assert_eq!(linear(&TABLE, 5, 1), Some(2));
+        assert_eq!(linear(&TABLE, 5, 7), Some(18));
+        assert_eq!(linear(&TABLE, 5, 12), Some(10));
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_table() {
+        assert_eq!(linear(&TABLE, 5, 16), None);
+        assert_eq!(linear(&TABLE, 5, u8::MAX), None);
+    }
+
+    #[test]
+    fn does_not_blend_towards_out_of_reach() {
+        let table = [0, 40, OUT_OF_REACH, OUT_OF_REACH];
+        assert_eq!(linear(&table, 5, 5), Some(40));
+        assert_eq!(linear(&table, 5, 6), None);
+        assert_eq!(linear(&table, 5, 10), None);
+        assert_eq!(linear(&table, 5, 13), None);
+    }
+}
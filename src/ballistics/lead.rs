@@ -0,0 +1,82 @@
+use crate::{ballistics::Holdover, fixed::Fixed};
+
+/// Which way the target crosses the line of sight.
+#[derive(PartialEq, Clone, Copy)]
+pub enum TargetDirection {
+    Left,
+    Right,
+}
+
+/// A target moving across the line of sight.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Lead {
+    pub enabled: bool,
+    /// Target speed in km/h.
+    pub speed_kmh: u8,
+    pub direction: TargetDirection,
+}
+
+impl Lead {
+    pub const OFF: Lead = Lead {
+        enabled: false,
+        speed_kmh: 5,
+        direction: TargetDirection::Right,
+    };
+
+    /// Horizontal offset of the lead mark at `range` metres: where the BB
+    /// lands relative to the target, which moves on while the BB is in
+    /// flight. The BB lands behind it, so the mark goes to the side the
+    /// target comes from and putting it on the target puts the reticle ahead.
+    /// Positive values are to the right.
+    pub fn offset(&self, range: u8, flight_time_ms: i16) -> Holdover {
+        if range == 0 {
+            return Holdover::NONE;
+        }
+        // km/h times ms divided by 3600 is metres.
+        let travel = Fixed::from(flight_time_ms) / 3600 * Fixed::from(self.speed_kmh);
+        let mrad_tenths = (travel * 1000 / Fixed::from(range) * 10)
+            .round()
+            .clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        match self.direction {
+            TargetDirection::Right => Holdover::from_mrad_tenths(-mrad_tenths),
+            TargetDirection::Left => Holdover::from_mrad_tenths(mrad_tenths),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lead(speed_kmh: u8, direction: TargetDirection) -> Lead {
+        Lead {
+            enabled: true,
+            speed_kmh,
+            direction,
+        }
+    }
+
+    #[test]
+    fn target_moving_right_puts_the_mark_left() {
+        // 18 km/h is 5 m/s, half a metre in 100 ms, 25 mrad at 20 m.
+        let offset = lead(18, TargetDirection::Right).offset(20, 100);
+        assert_eq!(offset, Holdover::from_mrad_tenths(-250));
+    }
+
+    #[test]
+    fn target_moving_left_puts_the_mark_right() {
+        let offset = lead(18, TargetDirection::Left).offset(20, 100);
+        assert_eq!(offset, Holdover::from_mrad_tenths(250));
+    }
+
+    #[test]
+    fn stationary_target_needs_no_lead() {
+        for direction in [TargetDirection::Left, TargetDirection::Right] {
+            assert_eq!(lead(0, direction).offset(20, 100), Holdover::NONE);
+        }
+        assert_eq!(
+            lead(18, TargetDirection::Right).offset(0, 100),
+            Holdover::NONE
+        );
+    }
+}
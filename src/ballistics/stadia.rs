@@ -0,0 +1,81 @@
+use crate::{ballistics::PixelScale, fixed::Fixed};
+
+/// Something of known height to frame with the ranging brackets.
+pub struct StadiaTarget {
+    pub name: &'static str,
+    pub height_cm: u16,
+}
+
+pub const STADIA_TARGETS: [StadiaTarget; 3] = [
+    StadiaTarget {
+        name: "Person 1.7m",
+        height_cm: 170,
+    },
+    StadiaTarget {
+        name: "Torso 0.5m",
+        height_cm: 50,
+    },
+    StadiaTarget {
+        name: "Head 0.25m",
+        height_cm: 25,
+    },
+];
+
+/// Labels of `STADIA_TARGETS`, for choosing one in the settings.
+pub const STADIA_TARGET_NAMES: [&str; STADIA_TARGETS.len()] = [
+    STADIA_TARGETS[0].name,
+    STADIA_TARGETS[1].name,
+    STADIA_TARGETS[2].name,
+];
+
+/// Rows of the top and bottom ranging brackets around `center_y`, exactly
+/// `spacing_px` apart so the gap drawn is the one ranged with.
+pub fn bracket_rows(center_y: i32, spacing_px: i16) -> [i32; 2] {
+    let top = center_y - spacing_px as i32 / 2;
+    [top, top + spacing_px as i32]
+}
+
+/// Range in metres to a target `height_cm` tall that spans `spacing_px`
+/// display pixels, saturating at 255 m.
+pub fn range(height_cm: u16, spacing_px: i16, scale: PixelScale) -> u8 {
+    if spacing_px <= 0 {
+        return u8::MAX;
+    }
+    // metres = height / angle, with the angle in milliradians.
+    let mrad = scale.mrad(spacing_px);
+    let metres = Fixed::from(height_cm as i16) * 10 / mrad;
+    metres.round().clamp(0, u8::MAX as i32) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Five pixels per milliradian.
+    const SCALE: PixelScale = PixelScale {
+        pixels_per_mrad: 500,
+    };
+
+    #[test]
+    fn ranges_a_target_of_known_height() {
+        // A 50 cm torso at 20 m spans 25 mrad.
+        assert_eq!(range(STADIA_TARGETS[1].height_cm, 125, SCALE), 20);
+        // Twice as far it spans half as much.
+        assert_eq!(range(STADIA_TARGETS[1].height_cm, 62, SCALE), 40);
+    }
+
+    #[test]
+    fn brackets_span_the_whole_spacing() {
+        for spacing in [0, 1, 24, 25, 125] {
+            let [top, bottom] = bracket_rows(32, spacing);
+            assert_eq!(bottom - top, spacing as i32);
+            assert!(top <= 32 && bottom >= 32);
+        }
+    }
+
+    #[test]
+    fn saturates_targets_too_small_to_frame() {
+        assert_eq!(range(STADIA_TARGETS[0].height_cm, 0, SCALE), u8::MAX);
+        assert_eq!(range(STADIA_TARGETS[0].height_cm, 1, SCALE), u8::MAX);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{ballistics::Holdover, fixed::Fixed};
+
+/// Wind as entered by the user.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Wind {
+    /// Wind speed in m/s.
+    pub speed: u8,
+    /// Clock direction the wind blows from, 12 is straight ahead and 3 is
+    /// from the right.
+    pub clock: u8,
+}
+
+impl Wind {
+    pub const CALM: Wind = Wind { speed: 0, clock: 3 };
+
+    /// Wind component blowing from right to left in m/s.
+    pub fn crosswind(&self) -> Fixed {
+        let angle = Fixed::from(self.clock % 12) * Fixed::PI / 6;
+        Fixed::from(self.speed) * angle.sin()
+    }
+
+    /// Horizontal correction at `range` metres, using the lag-time rule:
+    /// the drift is the crosswind times the difference between the actual
+    /// flight time and the flight time in vacuum. Positive values mean the BB
+    /// lands right of the line of sight, so a wind from the right moves the
+    /// aim point left, where the BB drifts to.
+    pub fn windage(&self, range: u8, flight_time_ms: i16, muzzle_velocity: Fixed) -> Holdover {
+        if range == 0 || self.speed == 0 {
+            return Holdover::NONE;
+        }
+        let range = Fixed::from(range);
+        let vacuum_time = range / muzzle_velocity;
+        let lag = Fixed::from(flight_time_ms) / 1000 - vacuum_time;
+        // The crosswind blows from right to left, a positive one drifts the
+        // BB left.
+        let drift = -self.crosswind() * lag;
+        // Divide before scaling up to tenths so metres of drift fit Q16.16.
+        let mrad_tenths = drift * 1000 / range * 10;
+        Holdover::from_mrad_tenths(
+            mrad_tenths.round().clamp(i16::MIN as i32, i16::MAX as i32) as i16
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Windage at 30 m for a BB leaving at 100 m/s and taking 400 ms, a
+    /// tenth of a second longer than in vacuum.
+    fn windage(clock: u8) -> i16 {
+        let wind = Wind { speed: 5, clock };
+        wind.windage(30, 400, Fixed::from_int(100)).mrad_tenths
+    }
+
+    #[test]
+    fn wind_from_the_right_moves_the_aim_point_left() {
+        // Half a metre of drift at 30 m.
+        assert!((windage(3) + 167).abs() <= 1, "{}", windage(3));
+    }
+
+    #[test]
+    fn wind_from_the_left_moves_the_aim_point_right() {
+        assert!((windage(9) - 167).abs() <= 1, "{}", windage(9));
+        assert_eq!(windage(9), -windage(3));
+    }
+
+    #[test]
+    fn head_and_tail_winds_do_not_drift() {
+        assert_eq!(windage(12), 0);
+        assert_eq!(windage(6), 0);
+        assert_eq!(
+            Wind::CALM.windage(30, 400, Fixed::from_int(100)),
+            Holdover::NONE
+        );
+    }
+}
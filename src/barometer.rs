@@ -0,0 +1,199 @@
+use embedded_hal::i2c::I2c;
+
+/// Air conditions at the sight.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct AirReading {
+    pub temperature_c: i8,
+    /// Absolute pressure in hPa.
+    pub pressure_hpa: u16,
+}
+
+/// Something that can measure the temperature and pressure of the air.
+pub trait Barometer {
+    type Error;
+
+    fn read(&mut self) -> Result<AirReading, Self::Error>;
+}
+
+const BME280_ADDRESS: u8 = 0x76;
+const BME280_CALIBRATION: u8 = 0x88;
+const BME280_CTRL_MEAS: u8 = 0xF4;
+const BME280_PRESS_MSB: u8 = 0xF7;
+/// Temperature and pressure oversampling x1, normal mode.
+#[allow(clippy::unusual_byte_groupings)] // Grouped by register field.
+const BME280_MEASURE_CONTINUOUSLY: u8 = 0b001_001_11;
+
+/// Factory trim values used by the compensation formulas, named after the
+/// datasheet.
+#[derive(Default)]
+struct Calibration {
+    t1: u16,
+    t2: i16,
+    t3: i16,
+    p1: u16,
+    p2: i16,
+    p3: i16,
+    p4: i16,
+    p5: i16,
+    p6: i16,
+    p7: i16,
+    p8: i16,
+    p9: i16,
+}
+
+/// Bosch BME280 (or BMP280) on the I2C bus. Humidity is not read, its effect
+/// on BB flight is negligible.
+pub struct Bme280<I2C> {
+    i2c: I2C,
+    calibration: Calibration,
+}
+
+impl<I2C: I2c> Bme280<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            calibration: Calibration::default(),
+        }
+    }
+
+    /// Reads the factory calibration and starts continuous measurements.
+    pub fn init(&mut self) -> Result<(), I2C::Error> {
+        let mut buffer = [0u8; 24];
+        self.i2c
+            .write_read(BME280_ADDRESS, &[BME280_CALIBRATION], &mut buffer)?;
+        let unsigned = |index: usize| u16::from_le_bytes([buffer[index], buffer[index + 1]]);
+        let signed = |index: usize| unsigned(index) as i16;
+        self.calibration = Calibration {
+            t1: unsigned(0),
+            t2: signed(2),
+            t3: signed(4),
+            p1: unsigned(6),
+            p2: signed(8),
+            p3: signed(10),
+            p4: signed(12),
+            p5: signed(14),
+            p6: signed(16),
+            p7: signed(18),
+            p8: signed(20),
+            p9: signed(22),
+        };
+        self.i2c.write(
+            BME280_ADDRESS,
+            &[BME280_CTRL_MEAS, BME280_MEASURE_CONTINUOUSLY],
+        )
+    }
+}
+
+// The compensation does not need the bus.
+impl<I2C> Bme280<I2C> {
+    /// Temperature in hundredths of a degree and the `t_fine` value the
+    /// pressure compensation needs, following the datasheet.
+    fn compensate_temperature(&self, raw: i32) -> (i32, i32) {
+        let c = &self.calibration;
+        let var1 = (((raw >> 3) - ((c.t1 as i32) << 1)) * c.t2 as i32) >> 11;
+        let delta = (raw >> 4) - c.t1 as i32;
+        let var2 = (((delta * delta) >> 12) * c.t3 as i32) >> 14;
+        let t_fine = var1 + var2;
+        ((t_fine * 5 + 128) >> 8, t_fine)
+    }
+
+    /// Pressure in Pa, using the 32-bit integer formula from the datasheet
+    /// since 64-bit division is expensive on the AVR.
+    fn compensate_pressure(&self, raw: i32, t_fine: i32) -> u32 {
+        let c = &self.calibration;
+        let mut var1 = (t_fine >> 1) - 64000;
+        let mut var2 = (((var1 >> 2) * (var1 >> 2)) >> 11) * c.p6 as i32;
+        var2 += (var1 * c.p5 as i32) << 1;
+        var2 = (var2 >> 2) + ((c.p4 as i32) << 16);
+        var1 = (((c.p3 as i32 * (((var1 >> 2) * (var1 >> 2)) >> 13)) >> 3)
+            + ((c.p2 as i32 * var1) >> 1))
+            >> 18;
+        var1 = ((32768 + var1) * c.p1 as i32) >> 15;
+        if var1 == 0 {
+            return 0;
+        }
+        let mut pressure = ((1_048_576 - raw) as u32)
+            .wrapping_sub((var2 >> 12) as u32)
+            .wrapping_mul(3125);
+        pressure = if pressure < 0x8000_0000 {
+            (pressure << 1) / var1 as u32
+        } else {
+            (pressure / var1 as u32) * 2
+        };
+        let var1 = (c.p9 as i32 * ((((pressure >> 3) * (pressure >> 3)) >> 13) as i32)) >> 12;
+        let var2 = (((pressure >> 2) as i32) * c.p8 as i32) >> 13;
+        (pressure as i32 + ((var1 + var2 + c.p7 as i32) >> 4)) as u32
+    }
+}
+
+impl<I2C: I2c> Barometer for Bme280<I2C> {
+    type Error = I2C::Error;
+
+    fn read(&mut self) -> Result<AirReading, Self::Error> {
+        let mut buffer = [0u8; 6];
+        self.i2c
+            .write_read(BME280_ADDRESS, &[BME280_PRESS_MSB], &mut buffer)?;
+        let twenty_bit = |index: usize| {
+            ((buffer[index] as i32) << 12)
+                | ((buffer[index + 1] as i32) << 4)
+                | (buffer[index + 2] as i32 >> 4)
+        };
+        let (temperature, t_fine) = self.compensate_temperature(twenty_bit(3));
+        let pressure = self.compensate_pressure(twenty_bit(0), t_fine);
+        Ok(AirReading {
+            temperature_c: ((temperature + 50) / 100).clamp(i8::MIN as i32, i8::MAX as i32) as i8,
+            pressure_hpa: ((pressure + 50) / 100) as u16,
+        })
+    }
+}
+
+/// Returns a fixed reading, for exercising the maths on the host without a
+/// sensor.
+#[cfg(test)]
+pub struct MockBarometer {
+    pub reading: AirReading,
+}
+
+#[cfg(test)]
+impl Barometer for MockBarometer {
+    type Error = core::convert::Infallible;
+
+    fn read(&mut self) -> Result<AirReading, Self::Error> {
+        Ok(self.reading)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Calibration from the example in section 8.1 of the BMP280
+    /// datasheet.
+    const DATASHEET_CALIBRATION: Calibration = Calibration {
+        t1: 27504,
+        t2: 26435,
+        t3: -1000,
+        p1: 36477,
+        p2: -10685,
+        p3: 3024,
+        p4: 2855,
+        p5: 140,
+        p6: -7,
+        p7: 15500,
+        p8: -14600,
+        p9: 6000,
+    };
+
+    #[test]
+    fn compensates_the_datasheet_example() {
+        let sensor = Bme280 {
+            i2c: (),
+            calibration: DATASHEET_CALIBRATION,
+        };
+        let (temperature, t_fine) = sensor.compensate_temperature(519888);
+        assert_eq!(temperature, 2508);
+        assert_eq!(t_fine, 128422);
+        let pressure = sensor.compensate_pressure(415148, t_fine);
+        assert!((pressure as i32 - 100653).abs() <= 10, "{pressure} Pa");
+    }
+}
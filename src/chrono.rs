@@ -0,0 +1,138 @@
+use crate::sight::MAX_CHRONO_VELOCITY;
+
+/// Parses velocity readings streamed by a chronograph over the serial port.
+///
+/// Each reading is one line holding a decimal number, optionally followed by
+/// a unit: `95.3`, `95.3 m/s` or `312 fps`. Readings without a unit are taken
+/// to be in m/s. Lines that do not match are ignored, as are velocities that
+/// round to 0 or to more than `MAX_CHRONO_VELOCITY`.
+pub struct ChronoParser {
+    state: State,
+    /// Value read so far, in tenths of the unit.
+    tenths: u32,
+    unit: [u8; 3],
+    unit_len: u8,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum State {
+    /// Start of a line, nothing read yet.
+    Start,
+    Whole,
+    Fraction {
+        digits: u8,
+    },
+    Unit,
+    /// Skipping until the end of a malformed line.
+    Invalid,
+}
+
+/// Feet per second in m/s, times 10000.
+const FPS_TO_MPS_E4: u32 = 3048;
+
+impl ChronoParser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Start,
+            tenths: 0,
+            unit: [0; 3],
+            unit_len: 0,
+        }
+    }
+
+    /// Feeds one received byte, returns the velocity in m/s once a complete
+    /// reading has been parsed.
+    pub fn push(&mut self, byte: u8) -> Option<u8> {
+        if byte == b'\r' || byte == b'\n' {
+            let velocity = self.finish();
+            self.reset();
+            return velocity;
+        }
+        self.state = match (self.state, byte) {
+            (State::Start | State::Whole, b'0'..=b'9') => {
+                self.tenths = self.tenths * 10 + (byte - b'0') as u32 * 10;
+                State::Whole
+            }
+            (State::Whole, b'.') => State::Fraction { digits: 0 },
+            (State::Fraction { digits: 0 }, b'0'..=b'9') => {
+                self.tenths += (byte - b'0') as u32;
+                State::Fraction { digits: 1 }
+            }
+            // Digits past the first decimal are not needed.
+            (State::Fraction { digits }, b'0'..=b'9') => State::Fraction { digits },
+            (State::Start, b' ') => State::Start,
+            (State::Whole | State::Fraction { .. } | State::Unit, b' ') => State::Unit,
+            (
+                State::Whole | State::Fraction { .. } | State::Unit,
+                b'a'..=b'z' | b'A'..=b'Z' | b'/',
+            ) => {
+                if self.unit_len as usize == self.unit.len() {
+                    State::Invalid
+                } else {
+                    self.unit[self.unit_len as usize] = byte.to_ascii_lowercase();
+                    self.unit_len += 1;
+                    State::Unit
+                }
+            }
+            _ => State::Invalid,
+        };
+        // Guard against overflow from absurdly long numbers.
+        if self.tenths > 100_000 {
+            self.state = State::Invalid;
+        }
+        None
+    }
+
+    fn finish(&self) -> Option<u8> {
+        let mps_tenths = match (self.state, &self.unit[..self.unit_len as usize]) {
+            (State::Whole | State::Fraction { .. } | State::Unit, b"" | b"m/s" | b"ms") => {
+                self.tenths
+            }
+            (State::Whole | State::Fraction { .. } | State::Unit, b"fps") => {
+                self.tenths * FPS_TO_MPS_E4 / 10_000
+            }
+            _ => return None,
+        };
+        match (mps_tenths + 5) / 10 {
+            0 => None,
+            velocity if velocity > MAX_CHRONO_VELOCITY as u32 => None,
+            velocity => Some(velocity as u8),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &[u8]) -> Option<u8> {
+        let mut parser = ChronoParser::new();
+        line.iter().find_map(|byte| parser.push(*byte))
+    }
+
+    #[test]
+    fn reads_metres_and_feet_per_second() {
+        assert_eq!(parse(b"95.3\n"), Some(95));
+        assert_eq!(parse(b"95.6 m/s\r\n"), Some(96));
+        assert_eq!(parse(b"312 fps\n"), Some(95));
+    }
+
+    #[test]
+    fn ignores_malformed_lines() {
+        assert_eq!(parse(b"9x5\n"), None);
+        assert_eq!(parse(b"95 km/h\n"), None);
+        assert_eq!(parse(b"0.2\n"), None);
+    }
+
+    #[test]
+    fn rejects_velocities_past_the_maximum() {
+        assert_eq!(parse(b"250.4\n"), Some(MAX_CHRONO_VELOCITY));
+        assert_eq!(parse(b"250.5\n"), None);
+        assert_eq!(parse(b"255\n"), None);
+        assert_eq!(parse(b"900 fps\n"), None);
+    }
+}
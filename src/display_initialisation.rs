@@ -1,4 +1,7 @@
+use core::cell::Cell;
+
 use display_interface::DisplayError;
+use crate::appearance::{MAX_BRIGHTNESS, NIGHT_VISION_BRIGHTNESS};
 use crate::embedded_graphics_transform::{ FlipY};
 use embedded_hal::delay::DelayNs;
 use ssd1351::mode::GraphicsMode;
@@ -24,16 +27,64 @@ use ssd1351::{
     builder::Builder,
     properties::{DisplayRotation, DisplaySize},
 };
-pub struct SpiWrapper<CSPIN>
+/// Brightness step waiting to be sent to the panel. The driver owns the
+/// interface once the display is built, so `SpiWrapper` sends it ahead of
+/// the next command the driver gives it.
+pub type PendingBrightness = Cell<Option<u8>>;
+
+const SET_CONTRAST_ABC: u8 = 0xC1;
+const SET_MASTER_CURRENT: u8 = 0xC7;
+
+/// Master current (0-15) and red, green and blue contrast for each
+/// brightness step. The top step is the panel's usual setting.
+const BRIGHTNESS_STEPS: [(u8, [u8; 3]); MAX_BRIGHTNESS as usize] = [
+    (0x01, [0x40, 0x28, 0x40]),
+    (0x03, [0x60, 0x3C, 0x60]),
+    (0x06, [0x80, 0x50, 0x80]),
+    (0x0A, [0xA0, 0x64, 0xA0]),
+    (0x0F, [0xC8, 0x80, 0xC8]),
+];
+/// Lowest master current and contrasts, for night vision devices.
+const NIGHT_VISION_STEP: (u8, [u8; 3]) = (0x00, [0x20, 0x18, 0x20]);
+
+pub struct SpiWrapper<'a, CSPIN>
 where
     CSPIN: port::PinOps,
 {
     spi: (Spi, ChipSelectPin<CSPIN>),
     dc: arduino_hal::port::Pin<arduino_hal::port::mode::Output>,
+    brightness: &'a PendingBrightness,
 }
 const BUFFER_SIZE: usize = 64;
 
-impl<CSPIN> WriteOnlyDataCommand for SpiWrapper<CSPIN>
+impl<'a, CSPIN> SpiWrapper<'a, CSPIN>
+where
+    CSPIN: port::PinOps,
+{
+    fn send_brightness(&mut self, brightness: u8) -> Result<(), DisplayError> {
+        let (current, contrast) = match brightness {
+            NIGHT_VISION_BRIGHTNESS => NIGHT_VISION_STEP,
+            _ => BRIGHTNESS_STEPS[brightness.min(MAX_BRIGHTNESS) as usize - 1],
+        };
+        self.send_command(SET_MASTER_CURRENT, &[current])?;
+        self.send_command(SET_CONTRAST_ABC, &contrast)
+    }
+
+    fn send_command(&mut self, command: u8, arguments: &[u8]) -> Result<(), DisplayError> {
+        self.dc.set_low();
+        self.spi
+            .0
+            .write(&[command])
+            .map_err(|_| DisplayError::BusWriteError)?;
+        self.dc.set_high();
+        self.spi
+            .0
+            .write(arguments)
+            .map_err(|_| DisplayError::BusWriteError)
+    }
+}
+
+impl<'a, CSPIN> WriteOnlyDataCommand for SpiWrapper<'a, CSPIN>
 where
     CSPIN: port::PinOps,
 {
@@ -41,6 +92,11 @@ where
         &mut self,
         commands: display_interface::DataFormat,
     ) -> Result<(), display_interface::DisplayError> {
+        // Between two commands the panel is not expecting data, so this is
+        // the one place the brightness can be slipped in.
+        if let Some(brightness) = self.brightness.take() {
+            self.send_brightness(brightness)?;
+        }
         // Implement the logic to send commands over SPI
         self.dc.set_low();
         send_u8(&mut self.spi.0, commands)
@@ -76,7 +132,10 @@ where
     }
 }
 
+/// Builds the display. Brightness steps put in `brightness` are applied
+/// with the next drawing.
 pub fn create_display(
+    brightness: &PendingBrightness,
     spi: SPI,
     mut cs: Pin<Output, PB2>,
     clk: Pin<Output, PB5>,
@@ -84,7 +143,7 @@ pub fn create_display(
     mut rst: Pin<Output, Dynamic>,
     mut dc: Pin<Output, Dynamic>,
     miso: Pin<Input<PullUp>, PB4>,
-) -> FlipY<GraphicsMode<SpiWrapper<PB2>>> {
+) -> FlipY<GraphicsMode<SpiWrapper<'_, PB2>>> {
     cs.set_low();
     dc.set_low();
     rst.set_low();
@@ -103,7 +162,11 @@ pub fn create_display(
     let mut interface: GraphicsMode<_> = Builder::new()
         .with_rotation(DisplayRotation::Rotate0)
         .with_size(DisplaySize::Display128x96)
-        .connect_interface(SpiWrapper { spi, dc })
+        .connect_interface(SpiWrapper {
+            spi,
+            dc,
+            brightness,
+        })
         .into();
     interface
         .reset(
@@ -3,6 +3,19 @@
 use embedded_hal::digital::InputPin;
 
 
+/// Milliseconds the button has to stay down for a long press.
+const LONG_PRESS_MS: u32 = 1000;
+
+/// What the push button did since it was last polled.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ButtonEvent {
+    None,
+    /// Pressed and released before a long press.
+    Click,
+    /// Held down for `LONG_PRESS_MS`, reported once while still held.
+    LongPress,
+}
+
 /// A simple rotary encoder with optional push button.
 pub struct RotaryEncoder<A, B, SW>
 where
@@ -15,6 +28,10 @@ where
     pin_sw: SW,
     last_a: bool,
     position: i32,
+    /// When the button went down, `None` while released.
+    pressed_at: Option<u32>,
+    /// Whether the current press was reported as a long press already.
+    long_pressed: bool,
 }
 
 impl<A, B, SW> RotaryEncoder<A, B, SW>
@@ -31,6 +48,8 @@ where
             pin_sw,
             last_a: initial_a,
             position: 0,
+            pressed_at: None,
+            long_pressed: false,
         })
     }
 
@@ -63,8 +82,129 @@ where
         self.position = 0;
     }
 
+    /// Moves the position counter, so rotation continues from `position`.
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
     /// Returns true if the button is pressed.
     pub fn is_pressed(&mut self) -> Result<bool, SW::Error> {
         self.pin_sw.is_low()
     }
+
+    /// Call regularly to tell clicks from long presses, `now_ms` from a
+    /// millisecond timer so how long the button is held does not depend on
+    /// how long the main loop takes.
+    pub fn button_event(&mut self, now_ms: u32) -> Result<ButtonEvent, SW::Error> {
+        if self.is_pressed()? {
+            let pressed_at = *self.pressed_at.get_or_insert(now_ms);
+            if !self.long_pressed && now_ms.wrapping_sub(pressed_at) >= LONG_PRESS_MS {
+                self.long_pressed = true;
+                return Ok(ButtonEvent::LongPress);
+            }
+            return Ok(ButtonEvent::None);
+        }
+        let was_pressed = self.pressed_at.take().is_some();
+        let long_pressed = core::mem::replace(&mut self.long_pressed, false);
+        Ok(if was_pressed && !long_pressed {
+            ButtonEvent::Click
+        } else {
+            ButtonEvent::None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    /// A pin reading whatever the test last set, shared so it can change
+    /// after the encoder took it.
+    #[derive(Clone, Copy)]
+    struct MockPin<'a>(&'a Cell<bool>);
+
+    impl ErrorType for MockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.get())
+        }
+
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.get())
+        }
+    }
+
+    /// Events for the button read at `times`, held down (low) from `down`
+    /// until `up`.
+    fn events(down: u32, up: u32, times: impl IntoIterator<Item = u32>) -> Vec<ButtonEvent> {
+        let high = Cell::new(true);
+        let button = Cell::new(true);
+        let mut encoder =
+            RotaryEncoder::new(MockPin(&high), MockPin(&high), MockPin(&button)).unwrap();
+        times
+            .into_iter()
+            .map(|now| {
+                button.set(!(down..up).contains(&now));
+                encoder.button_event(now).unwrap()
+            })
+            .filter(|event| *event != ButtonEvent::None)
+            .collect()
+    }
+
+    #[test]
+    fn short_press_clicks_on_release() {
+        assert_eq!(
+            events(100, 400, (0..1000).step_by(50)),
+            [ButtonEvent::Click]
+        );
+    }
+
+    #[test]
+    fn long_press_is_reported_once_while_held() {
+        assert_eq!(
+            events(100, 3000, (0..4000).step_by(50)),
+            [ButtonEvent::LongPress]
+        );
+    }
+
+    #[test]
+    fn long_press_does_not_depend_on_the_poll_rate() {
+        // A slow loop reads the button a handful of times only.
+        assert_eq!(
+            events(0, 1200, [0, 600, 1100, 1300]),
+            [ButtonEvent::LongPress]
+        );
+        assert_eq!(events(0, 900, [0, 600, 950]), [ButtonEvent::Click]);
+        // A fast one reads it thousands of times within the second.
+        assert_eq!(events(0, 900, 0..1000), [ButtonEvent::Click]);
+    }
+
+    #[test]
+    fn long_press_across_the_timer_wrapping() {
+        let down = u32::MAX - 500;
+        let times = [down, u32::MAX, 400, 600];
+        let high = Cell::new(true);
+        let button = Cell::new(false);
+        let mut encoder =
+            RotaryEncoder::new(MockPin(&high), MockPin(&high), MockPin(&button)).unwrap();
+        let events: Vec<_> = times
+            .into_iter()
+            .map(|now| encoder.button_event(now).unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ButtonEvent::None,
+                ButtonEvent::None,
+                ButtonEvent::None,
+                ButtonEvent::LongPress
+            ]
+        );
+    }
 }
@@ -0,0 +1,434 @@
+// This file is also compiled into `build.rs`, so it must only depend on `core`.
+
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Signed Q16.16 fixed-point number.
+///
+/// The ATmega328P has no FPU, so everything that runs on the device uses this
+/// instead of `f32`. Arithmetic saturates instead of wrapping around.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+pub struct Fixed(i32);
+
+const FRAC_BITS: u32 = 16;
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << FRAC_BITS);
+    pub const HALF: Fixed = Fixed(1 << (FRAC_BITS - 1));
+    pub const MAX: Fixed = Fixed(i32::MAX);
+    pub const MIN: Fixed = Fixed(i32::MIN);
+    pub const PI: Fixed = Fixed(205_887);
+    pub const FRAC_PI_2: Fixed = Fixed(102_944);
+
+    pub const fn from_raw(raw: i32) -> Self {
+        Fixed(raw)
+    }
+
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i16) -> Self {
+        Fixed((value as i32) << FRAC_BITS)
+    }
+
+    /// `numerator / denominator`, rounded towards zero.
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+        Fixed((((numerator as i64) << FRAC_BITS) / denominator as i64) as i32)
+    }
+
+    /// Only meant for constants, the conversion is evaluated at compile time.
+    pub const fn from_f32(value: f32) -> Self {
+        Fixed((value * (1 << FRAC_BITS) as f32) as i32)
+    }
+
+    /// Integer part, rounded towards negative infinity.
+    pub const fn floor(self) -> i32 {
+        self.0 >> FRAC_BITS
+    }
+
+    /// Nearest integer, halves rounded away from zero.
+    pub const fn round(self) -> i32 {
+        if self.0 < 0 {
+            -((self.0.saturating_neg() + Self::HALF.0) >> FRAC_BITS)
+        } else {
+            self.0.saturating_add(Self::HALF.0) >> FRAC_BITS
+        }
+    }
+
+    pub const fn abs(self) -> Self {
+        Fixed(self.0.saturating_abs())
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Square root, negative values yield zero.
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Fixed::ZERO;
+        }
+        // sqrt(raw / 2^16) * 2^16 == sqrt(raw * 2^16)
+        let value = (self.0 as u64) << FRAC_BITS;
+        let mut remainder = value;
+        let mut result: u64 = 0;
+        let mut bit: u64 = 1 << 62;
+        while bit > value {
+            bit >>= 2;
+        }
+        while bit != 0 {
+            if remainder >= result + bit {
+                remainder -= result + bit;
+                result = (result >> 1) + bit;
+            } else {
+                result >>= 1;
+            }
+            bit >>= 2;
+        }
+        Fixed(result as i32)
+    }
+
+    /// Sine of an angle in radians.
+    pub fn sin(self) -> Self {
+        let two_pi = Fixed(Self::PI.0 * 2);
+        let mut angle = Fixed(self.0 % two_pi.0);
+        if angle > Self::PI {
+            angle -= two_pi;
+        } else if angle < -Self::PI {
+            angle += two_pi;
+        }
+        // Fold into [-pi/2, pi/2] where the Taylor series converges quickly.
+        if angle > Self::FRAC_PI_2 {
+            angle = Self::PI - angle;
+        } else if angle < -Self::FRAC_PI_2 {
+            angle = -Self::PI - angle;
+        }
+        let squared = angle * angle;
+        let series = Fixed::ONE
+            - squared
+                * (Fixed::from_ratio(1, 6)
+                    - squared
+                        * (Fixed::from_ratio(1, 120)
+                            - squared * (Fixed::from_ratio(1, 5040) - squared / 362_880)));
+        angle * series
+    }
+
+    /// Cosine of an angle in radians.
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+
+    /// Tangent of an angle in radians, saturating near the poles.
+    pub fn tan(self) -> Self {
+        self.sin() / self.cos()
+    }
+
+    /// Arctangent in radians.
+    pub fn atan(self) -> Self {
+        if self.abs() > Fixed::ONE {
+            let reflected = Self::FRAC_PI_2 - (Fixed::ONE / self.abs()).atan();
+            return if self.is_negative() {
+                -reflected
+            } else {
+                reflected
+            };
+        }
+        // Minimax polynomial for |x| <= 1, accurate to about 1e-5 rad.
+        let squared = self * self;
+        let series = Fixed::from_f32(0.999_866)
+            + squared
+                * (Fixed::from_f32(-0.330_299_5)
+                    + squared
+                        * (Fixed::from_f32(0.180_141)
+                            + squared
+                                * (Fixed::from_f32(-0.085_133)
+                                    + squared * Fixed::from_f32(0.020_835_1))));
+        self * series
+    }
+
+    /// Angle of the vector `(x, self)` in radians, in `[-pi, pi]`.
+    pub fn atan2(self, x: Fixed) -> Self {
+        let y = self;
+        if x == Fixed::ZERO {
+            return match y.0 {
+                0 => Fixed::ZERO,
+                raw if raw > 0 => Self::FRAC_PI_2,
+                _ => -Self::FRAC_PI_2,
+            };
+        }
+        let angle = (y / x).atan();
+        match (x.is_negative(), y.is_negative()) {
+            (false, _) => angle,
+            (true, false) => angle + Self::PI,
+            (true, true) => angle - Self::PI,
+        }
+    }
+
+    /// Writes the value right-aligned into `buf` with `decimals` digits after
+    /// the decimal point, padding with spaces on the left. Values that do not
+    /// fit are shown as `*`.
+    pub fn format(self, decimals: u8, buf: &mut [u8]) {
+        assert!(decimals <= 4, "At most 4 decimals are supported");
+
+        let scale = 10u64.pow(decimals as u32);
+        let magnitude = (self.0 as i64).unsigned_abs();
+        let mut digits = (magnitude * scale + (1 << (FRAC_BITS - 1))) >> FRAC_BITS;
+        let negative = self.0 < 0 && digits != 0;
+
+        let mut index = buf.len();
+        let mut written = 0u8;
+        let fits = loop {
+            if decimals != 0 && written == decimals && !push(buf, &mut index, b'.') {
+                break false;
+            }
+            if !push(buf, &mut index, b'0' + (digits % 10) as u8) {
+                break false;
+            }
+            digits /= 10;
+            written += 1;
+            if digits == 0 && written > decimals {
+                break !negative || push(buf, &mut index, b'-');
+            }
+        };
+        if fits {
+            buf[..index].fill(b' ');
+        } else {
+            buf.fill(b'*');
+        }
+    }
+}
+
+/// Writes `byte` in front of `index`, returns `false` if `buf` is full.
+fn push(buf: &mut [u8], index: &mut usize, byte: u8) -> bool {
+    if *index == 0 {
+        return false;
+    }
+    *index -= 1;
+    buf[*index] = byte;
+    true
+}
+
+impl From<i16> for Fixed {
+    fn from(value: i16) -> Self {
+        Fixed::from_int(value)
+    }
+}
+
+impl From<u8> for Fixed {
+    fn from(value: u8) -> Self {
+        Fixed::from_int(value as i16)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: Fixed) -> Fixed {
+        let product = (self.0 as i64 * rhs.0 as i64) >> FRAC_BITS;
+        Fixed(product.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: Fixed) -> Fixed {
+        if rhs.0 == 0 {
+            return if self.0 < 0 { Fixed::MIN } else { Fixed::MAX };
+        }
+        let quotient = ((self.0 as i64) << FRAC_BITS) / rhs.0 as i64;
+        Fixed(quotient.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: i32) -> Fixed {
+        Fixed(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Div<i32> for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: i32) -> Fixed {
+        match self.0.checked_div(rhs) {
+            Some(quotient) => Fixed(quotient),
+            None if rhs == 0 && self.0 < 0 => Fixed::MIN,
+            // Dividing by zero, or `MIN` by -1 which is one past `MAX`.
+            None => Fixed::MAX,
+        }
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One step of Q16.16.
+    const RESOLUTION: f64 = 1.0 / 65536.0;
+
+    fn to_f64(value: Fixed) -> f64 {
+        value.raw() as f64 * RESOLUTION
+    }
+
+    fn fixed(value: f64) -> Fixed {
+        Fixed::from_raw((value / RESOLUTION).round() as i32)
+    }
+
+    /// `count` evenly spaced values from `start` up to `end`.
+    fn sweep(start: f64, end: f64, count: u32) -> impl Iterator<Item = Fixed> {
+        (0..=count).map(move |index| fixed(start + (end - start) * index as f64 / count as f64))
+    }
+
+    #[track_caller]
+    fn assert_close(actual: Fixed, expected: f64, tolerance: f64, input: Fixed) {
+        let error = (to_f64(actual) - expected).abs();
+        assert!(
+            error <= tolerance,
+            "{} for {} is off by {error}, expected {expected}",
+            to_f64(actual),
+            to_f64(input),
+        );
+    }
+
+    #[test]
+    fn sqrt_is_within_one_step() {
+        let mut raw = 1i64;
+        while raw <= i32::MAX as i64 {
+            let value = Fixed::from_raw(raw as i32);
+            assert_close(value.sqrt(), to_f64(value).sqrt(), RESOLUTION, value);
+            raw += raw / 50 + 1;
+        }
+        assert_eq!(Fixed::from_int(-4).sqrt(), Fixed::ZERO);
+    }
+
+    #[test]
+    fn sin_and_cos_are_within_2e_4() {
+        for angle in sweep(-20.0, 20.0, 50_000) {
+            assert_close(angle.sin(), to_f64(angle).sin(), 2e-4, angle);
+            assert_close(angle.cos(), to_f64(angle).cos(), 2e-4, angle);
+        }
+    }
+
+    #[test]
+    fn tan_is_within_1e_3_relative_away_from_the_poles() {
+        for angle in sweep(-20.0, 20.0, 50_000) {
+            let expected = to_f64(angle).tan();
+            if to_f64(angle).cos().abs() > 0.1 {
+                assert_close(angle.tan(), expected, 1e-3 * expected.abs().max(1.0), angle);
+            }
+        }
+    }
+
+    #[test]
+    fn atan_is_within_1e_4() {
+        for value in sweep(-300.0, 300.0, 100_000) {
+            assert_close(value.atan(), to_f64(value).atan(), 1e-4, value);
+        }
+    }
+
+    #[test]
+    fn atan2_is_within_1e_4_in_every_quadrant() {
+        for angle in sweep(-core::f64::consts::PI, core::f64::consts::PI, 3600) {
+            for radius in [0.01, 0.5, 1.0, 7.0, 100.0, 1000.0] {
+                let (y, x) = (
+                    fixed(radius * to_f64(angle).sin()),
+                    fixed(radius * to_f64(angle).cos()),
+                );
+                assert_close(y.atan2(x), to_f64(y).atan2(to_f64(x)), 1e-4, angle);
+            }
+        }
+        assert_eq!(Fixed::ZERO.atan2(Fixed::ZERO), Fixed::ZERO);
+        assert_eq!(Fixed::ONE.atan2(Fixed::ZERO), Fixed::FRAC_PI_2);
+        assert_eq!((-Fixed::ONE).atan2(Fixed::ZERO), -Fixed::FRAC_PI_2);
+    }
+
+    /// What `format` should write, from the value rounded in `f64` with
+    /// halves away from zero.
+    fn expected_format(value: Fixed, decimals: u8, width: usize) -> String {
+        let scale = 10f64.powi(decimals as i32);
+        let rounded = match (to_f64(value) * scale).round() {
+            0.0 => 0.0,
+            rounded => rounded / scale,
+        };
+        let text = format!(
+            "{rounded:>width$.precision$}",
+            precision = decimals as usize
+        );
+        if text.len() > width {
+            "*".repeat(width)
+        } else {
+            text
+        }
+    }
+
+    #[test]
+    fn format_matches_f64() {
+        let values = sweep(-1200.0, 1200.0, 20_000)
+            .chain(sweep(-1.0, 1.0, 5_000))
+            .chain([Fixed::MIN, Fixed::MAX, Fixed::HALF, -Fixed::HALF]);
+        for value in values {
+            for decimals in 0..=4 {
+                for width in [3, 5, 8, 12] {
+                    let mut buf = vec![0; width];
+                    value.format(decimals, &mut buf);
+                    assert_eq!(
+                        String::from_utf8(buf).unwrap(),
+                        expected_format(value, decimals, width),
+                        "{} with {decimals} decimals",
+                        to_f64(value),
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn division_saturates() {
+        assert_eq!(Fixed::ONE / 0, Fixed::MAX);
+        assert_eq!(-Fixed::ONE / 0, Fixed::MIN);
+        assert_eq!(Fixed::ZERO / 0, Fixed::MAX);
+        assert_eq!(Fixed::MIN / -1, Fixed::MAX);
+        assert_eq!(Fixed::from_int(-7) / 2, Fixed::from_ratio(-7, 2));
+        assert_eq!(Fixed::ONE / Fixed::ZERO, Fixed::MAX);
+        assert_eq!(Fixed::MIN / -Fixed::ONE, Fixed::MAX);
+    }
+}
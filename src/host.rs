@@ -0,0 +1,45 @@
+//! Crate root of `host-tests`, which builds the modules that do not touch the
+//! hardware for the PC so their unit tests can run there. They are declared
+//! the way `main.rs` declares them, so their `crate::` paths resolve.
+
+// Only the tests use the modules here, built without them nothing would.
+#![cfg(test)]
+// Much of what the modules export is only used by the firmware's `main.rs`
+// and settings menus, which are not built here.
+#![allow(dead_code, unused_imports)]
+
+mod accelerometer;
+mod ambient_light;
+mod appearance;
+mod ballistics;
+mod barometer;
+mod chrono;
+mod encoder;
+mod fixed;
+mod persistence;
+mod rangefinder;
+mod reticle;
+mod reticle_upload;
+mod ring_buffer;
+mod sight;
+mod subpixel;
+mod units;
+
+/// Stands in for `avr-progmem`, which only builds for the AVR on nightly. The
+/// tables stay in RAM, which the PC has plenty of.
+mod progmem {
+    pub struct ProgMem<T>(pub T);
+
+    impl<T: Copy, const N: usize> ProgMem<[T; N]> {
+        pub fn load_at(&self, index: usize) -> T {
+            self.0[index]
+        }
+    }
+
+    macro_rules! progmem {
+        ($(static progmem $name:ident: $ty:ty = $value:expr;)*) => {
+            $(static $name: $crate::progmem::ProgMem<$ty> = $crate::progmem::ProgMem($value);)*
+        };
+    }
+    pub(crate) use progmem;
+}
@@ -1,18 +1,36 @@
 #![no_std]
 #![no_main]
+#![feature(abi_avr_interrupt)]
+mod accelerometer;
+mod ambient_light;
+mod appearance;
+mod ballistics;
+mod barometer;
+mod chrono;
 mod display_initialisation;
 mod embedded_graphics_transform;
 mod encoder;
+mod fixed;
+mod millis;
+mod persistence;
+mod rangefinder;
+mod reticle;
+mod reticle_upload;
+mod ring_buffer;
 mod settings;
 mod sight;
+mod subpixel;
+mod units;
 
+use core::cell::{Cell, RefCell};
 use core::fmt::Debug;
 
 use arduino_hal::default_serial;
+use arduino_hal::prelude::*;
 use embedded_graphics::mono_font::ascii::FONT_6X10;
 use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::prelude::{DrawTarget, Primitive};
-use embedded_graphics::primitives::PrimitiveStyleBuilder;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder};
 use embedded_graphics::text::Text;
 use embedded_graphics::Drawable;
 use embedded_graphics::{
@@ -20,10 +38,44 @@ use embedded_graphics::{
     prelude::{Point, RgbColor},
 };
 use embedded_graphics_core::{prelude::Size, primitives::Rectangle};
+use embedded_hal_bus::i2c::RefCellDevice;
 
+use crate::accelerometer::{Accelerometer, Mpu6050};
+use crate::ambient_light::AmbientLightFilter;
+use crate::appearance::ReticleColor;
+use crate::ballistics::atmosphere::AtmosphereSource;
+use crate::ballistics::Holdover;
+use crate::barometer::{Barometer, Bme280};
+use crate::chrono::ChronoParser;
 use crate::display_initialisation::create_display;
-use crate::encoder::RotaryEncoder;
+use crate::encoder::{ButtonEvent, RotaryEncoder};
+use crate::fixed::Fixed;
+use crate::rangefinder::RangefinderParser;
+use crate::reticle::{CustomReticle, ReticleKind};
+use crate::reticle_upload::{ReticleUploadParser, MAX_LINE_BYTES};
+use crate::ring_buffer::RingBuffer;
 use crate::sight::Sight;
+use crate::units::Quantity;
+
+/// Main loop iterations between two accelerometer reads, reading on every
+/// iteration would make the encoder miss steps.
+const SENSOR_READ_INTERVAL: u16 = 200;
+/// Barometer reads between two holdover refreshes. Only standard air has
+/// precomputed tables, any other density integrates a new one, which stalls
+/// the loop, and the weather does not change that fast.
+const BAROMETER_REFRESH_READS: u8 = 50;
+
+/// Bytes `USART_RX` received, enough for the longest reticle upload line so
+/// one arriving while the loop redraws the display is not cut short.
+static SERIAL_RX: avr_device::interrupt::Mutex<RefCell<RingBuffer<MAX_LINE_BYTES>>> =
+    avr_device::interrupt::Mutex::new(RefCell::new(RingBuffer::new()));
+
+#[avr_device::interrupt(atmega328p)]
+fn USART_RX() {
+    // Reading the data register clears the interrupt.
+    let byte = unsafe { (*arduino_hal::pac::USART0::ptr()).udr0().read().bits() };
+    avr_device::interrupt::free(|cs| SERIAL_RX.borrow(cs).borrow_mut().push(byte));
+}
 
 #[arduino_hal::entry]
 fn main() -> ! {
@@ -36,36 +88,131 @@ fn main() -> ! {
     let dc = pins.d5.downgrade().into_output();
     let miso = pins.d12.into_pull_up_input();
 
-    let mut interface = create_display(dp.SPI, cs, clk, din, rst, dc, miso);
+    let brightness = Cell::new(None);
+    let mut interface = create_display(&brightness, dp.SPI, cs, clk, din, rst, dc, miso);
 
-    let mut sight = Sight {
-        x_zero: 0,
-        y_zero: 0,
-        battery_power: 15,
-        range: 33,
-    };
+    let mut eeprom = arduino_hal::Eeprom::new(dp.EEPROM);
+    let mut sight = Sight::new();
+    persistence::load(&mut eeprom, &mut sight);
+    // Kept out of `Sight`, which is copied to spot changes.
+    let mut custom_reticle = persistence::load_custom_reticle(&mut eeprom);
+    let mut applied_brightness = sight.brightness();
+    brightness.set(Some(applied_brightness));
     interface.clear_oled();
-    display_sight(&mut interface, &sight);
+    display_sight(&mut interface, &sight, &custom_reticle);
     let pin_a = pins.d2.into_pull_up_input();
     let pin_b = pins.d3.into_pull_up_input();
     let pin_sw = pins.d9.into_pull_up_input();
 
     let mut encoder = RotaryEncoder::new(pin_a, pin_b, pin_sw).unwrap();
+    // Times the long press, the loop takes longer while it redraws.
+    millis::init(dp.TC0);
+
+    let i2c = arduino_hal::I2c::new(
+        dp.TWI,
+        pins.a4.into_pull_up_input(),
+        pins.a5.into_pull_up_input(),
+        50000,
+    );
+    // The accelerometer and the barometer share the bus.
+    let i2c = RefCell::new(i2c);
+    let mut accelerometer = Mpu6050::new(RefCellDevice::new(&i2c));
+    let accelerometer_ready = accelerometer.init().is_ok();
+    let mut barometer = Bme280::new(RefCellDevice::new(&i2c));
+    let barometer_ready = barometer.init().is_ok();
+    let mut barometer_reads: u8 = 0;
+    let mut last_sensor_loop: u16 = 0;
+
+    let mut adc = arduino_hal::Adc::new(dp.ADC, Default::default());
+    let light_sensor = pins.a0.into_analog_input(&mut adc);
+    let mut ambient_light = AmbientLightFilter::new();
 
     let mut serial = default_serial!(dp, pins, 57600);
+    // The loop does not poll often enough for whole lines, `USART_RX`
+    // queues the bytes instead.
+    serial.listen(arduino_hal::usart::Event::RxComplete);
+    unsafe { avr_device::interrupt::enable() };
+    let mut chrono = ChronoParser::new();
+    let mut rangefinder = RangefinderParser::new();
+    let mut reticle_upload = ReticleUploadParser::new();
     let mut last_update_loop = 0;
-    let mut last_sight = sight;
+    let mut last_view = SightView::of(&sight);
     let mut settings_state = settings::SettingsState::new();
 
     loop {
         encoder.update().unwrap();
-        ufmt::uwriteln!(&mut serial, "position {}", encoder.position()).ok();
+        while let Some(byte) =
+            avr_device::interrupt::free(|cs| SERIAL_RX.borrow(cs).borrow_mut().pop())
+        {
+            if let Some(velocity) = chrono.push(byte) {
+                sight.set_chrono_velocity(velocity);
+                sight.refresh_holdover();
+                persistence::save(&mut eeprom, &sight);
+            }
+            if let Some(range) = rangefinder.push(byte) {
+                sight.measured_range = Some(range);
+                // The encoder drives the menus while they are open.
+                if sight.rangefinder_enabled && !settings_state.is_open() {
+                    sight.range = range;
+                    encoder.set_position(range as i32);
+                }
+            }
+            match reticle_upload.push(byte) {
+                Some(Ok(custom)) => {
+                    custom_reticle = custom;
+                    persistence::save_custom_reticle(&mut eeprom, &custom_reticle);
+                    ufmt::uwriteln!(&mut serial, "RET OK").ok();
+                    last_update_loop = 8000;
+                }
+                Some(Err(error)) => {
+                    ufmt::uwriteln!(&mut serial, "RET ERR {}", error.message()).ok();
+                }
+                None => {}
+            }
+        }
         last_update_loop += 1;
-        let settings_was_updated = settings_state.update(&mut sight, &mut encoder);
+        last_sensor_loop += 1;
+        let button = encoder
+            .button_event(millis::now())
+            .unwrap_or(ButtonEvent::None);
+        if button == ButtonEvent::LongPress && !settings_state.is_open() {
+            sight.night_vision = !sight.night_vision;
+            last_update_loop = 8000;
+        }
+        let sensors_due = last_sensor_loop > SENSOR_READ_INTERVAL;
+        let mut light_step_changed = false;
+        if sensors_due {
+            // Read with the menus open too, the light curve page shows it.
+            let light = ambient_light.push(light_sensor.analog_read(&mut adc));
+            let step = sight.ambient_brightness;
+            sight.set_ambient_light(light);
+            light_step_changed = sight.ambient_brightness != step;
+            last_sensor_loop = 0;
+        }
+        let settings_was_updated =
+            settings_state.update(&mut sight, &mut encoder, button == ButtonEvent::Click);
+        if sight.brightness() != applied_brightness {
+            applied_brightness = sight.brightness();
+            brightness.set(Some(applied_brightness));
+        }
         if settings_was_updated || settings_state.is_open() {
-            if settings_was_updated {
+            if settings_was_updated && !settings_state.is_open() {
+                // Integrating a new table takes a while, so it waits for the
+                // menus to close instead of following every step of a
+                // slider.
+                sight.refresh_holdover();
+                // The menus moved the encoder, carry on from the range they
+                // left.
+                encoder.set_position(sight.range as i32);
+                persistence::save(&mut eeprom, &sight);
+            }
+            // The light curve page reads out the light level, it is brought up
+            // to date when the light calls for another step rather than on
+            // every change. A new brightness step only reaches the panel with
+            // a drawing.
+            if settings_was_updated || light_step_changed || brightness.get().is_some() {
                 interface.clear_oled();
-                settings_state.draw(&mut interface, &sight);
+                settings_state.draw(&mut interface, &sight, &custom_reticle);
                 last_update_loop = 8000;
             }
         } else {
@@ -77,76 +224,230 @@ fn main() -> ! {
             if sight.range != position as u8 {
                 sight.range = position as u8;
             }
-            if (last_update_loop > 500 && last_sight != sight)
+            if sensors_due {
+                if accelerometer_ready {
+                    if let Ok(reading) = accelerometer.read() {
+                        sight.inclination_deg = reading.pitch_deg();
+                        sight.cant_deg = reading.roll_deg();
+                    }
+                }
+                if barometer_ready && sight.atmosphere.source == AtmosphereSource::Sensor {
+                    if let Ok(reading) = barometer.read() {
+                        sight.atmosphere.temperature_c = reading.temperature_c;
+                        sight.atmosphere.pressure_hpa = reading.pressure_hpa;
+                        if barometer_reads == 0 {
+                            sight.refresh_holdover();
+                        }
+                        barometer_reads = (barometer_reads + 1) % BAROMETER_REFRESH_READS;
+                    }
+                }
+            }
+            // A new brightness step waits in `brightness` until the next
+            // drawing sends it to the panel.
+            let brightness_pending = brightness.get().is_some();
+            let view = SightView::of(&sight);
+            if (last_update_loop > 500 && (brightness_pending || last_view != view))
                 || last_update_loop > 5000
-                || absolute_difference(last_sight.range, sight.range) > 8
+                || absolute_difference(last_view.range, view.range) > 8
             {
                 interface.clear_oled();
-                display_sight(&mut interface, &sight);
+                display_sight(&mut interface, &sight, &custom_reticle);
                 last_update_loop = 0;
-                last_sight = sight;
+                last_view = view;
             }
         }
     }
 }
 
-fn display_sight<T>(interface: &mut T, sight: &Sight)
+impl persistence::Storage for arduino_hal::Eeprom {
+    // Out of range accesses are a layout bug, reads leave the buffer as is
+    // and writes are dropped.
+    fn read(&mut self, address: u16, buffer: &mut [u8]) {
+        arduino_hal::Eeprom::read(self, address, buffer).ok();
+    }
+
+    fn write(&mut self, address: u16, data: &[u8]) {
+        arduino_hal::Eeprom::write(self, address, data).ok();
+    }
+}
+
+fn display_sight<T>(interface: &mut T, sight: &Sight, custom_reticle: &CustomReticle)
 where
     T: DrawTarget<Color = Rgb565, Error: Debug>,
 {
-    let mut buffer = *b"RNG: XXX";
-    write_value(interface, sight.range, Point::new(0, 76), &mut buffer);
+    let mut range_buffer = *b"RNG: XXXyd";
+    sight
+        .units
+        .format(Quantity::Range, sight.range as i16, &mut range_buffer[4..]);
+    if sight.night_vision {
+        // Nothing but the range, as dim as the reticle, so the night vision
+        // device is not flooded.
+        let color = sight.reticle_color().rgb();
+        write_colored_text(interface, &range_buffer, Point::new(0, 86), color);
+        draw_reticle(interface, sight, custom_reticle);
+        return;
+    }
+    // Red when the BB does not get that far.
+    let range_color = match sight.holdover() {
+        Some(_) => Rgb565::WHITE,
+        None => Rgb565::RED,
+    };
+    write_colored_text(interface, &range_buffer, Point::new(0, 76), range_color);
 
-    buffer = *b"PWR: XXX";
+    let mut buffer = *b"PWR: XXX";
     write_value(
         interface,
         sight.battery_power,
         Point::new(0, 86),
         &mut buffer,
     );
-    draw_reticle(interface, sight);
+
+    // Shorter label than the others to leave room for the unit.
+    let mut wind_buffer = *b"W:XXm/s@XXh";
+    sight.units.format(
+        Quantity::Velocity,
+        sight.wind.speed as i16,
+        &mut wind_buffer[2..7],
+    );
+    Fixed::from(sight.wind.clock).format(0, &mut wind_buffer[8..10]);
+    write_text(interface, &wind_buffer, Point::new(62, 76));
+
+    if sight.show_flight_time {
+        let mut flight_time_buffer = *b"TOF:-.--s";
+        if let Some(flight_time_ms) = sight.flight_time_ms() {
+            (Fixed::from(flight_time_ms) / 1000).format(2, &mut flight_time_buffer[4..8]);
+        }
+        write_text(interface, &flight_time_buffer, Point::new(0, 66));
+    }
+    if sight.show_energy {
+        let mut energy_buffer = *b"NRG:-.--J";
+        if let Some(energy) = sight.impact_energy() {
+            energy.format(2, &mut energy_buffer[4..8]);
+        }
+        write_text(interface, &energy_buffer, Point::new(66, 66));
+    }
+    draw_cant_indicator(interface, sight);
+    draw_reticle(interface, sight, custom_reticle);
+}
+
+/// Draws a horizon bar at the top of the display that stays level while the
+/// sight rolls, red once the cant exceeds the configured threshold.
+fn draw_cant_indicator<T>(interface: &mut T, sight: &Sight)
+where
+    T: DrawTarget<Color = Rgb565, Error: Debug>,
+{
+    let half_length = Fixed::from_int(16);
+    let center = Point::new(128 / 2, 10);
+    let angle = -Fixed::from(sight.cant_deg as i16) * Fixed::PI / 180;
+    let offset = Point::new(
+        (half_length * angle.cos()).round(),
+        (half_length * angle.sin()).round(),
+    );
+    let color = if sight.cant_deg.unsigned_abs() > sight.cant_threshold_deg {
+        Rgb565::RED
+    } else {
+        Rgb565::GREEN
+    };
+    Line::new(center - offset, center + offset)
+        .into_styled(PrimitiveStyle::with_stroke(color, 1))
+        .draw(interface)
+        .unwrap();
+    // Fixed reference tick marking the centre of the sight.
+    Line::new(center - Point::new(0, 3), center + Point::new(0, 3))
+        .into_styled(PrimitiveStyle::with_stroke(Rgb565::WHITE, 1))
+        .draw(interface)
+        .unwrap();
+}
+
+/// What the sight view shows that can change while the menus are closed,
+/// compared to tell when it needs redrawing. Everything else changes in the
+/// menus, which force a redraw when they close, and a second `Sight` with
+/// its holdover table would not fit in RAM next to the first.
+#[derive(PartialEq, Clone, Copy)]
+struct SightView {
+    range: u8,
+    holdover: Option<Holdover>,
+    inclination_deg: i8,
+    cant_deg: i8,
+    /// Follows the ambient light in the dark.
+    reticle_color: ReticleColor,
+}
+
+impl SightView {
+    fn of(sight: &Sight) -> Self {
+        SightView {
+            range: sight.range,
+            holdover: sight.holdover(),
+            inclination_deg: sight.inclination_deg,
+            cant_deg: sight.cant_deg,
+            reticle_color: sight.reticle_color(),
+        }
+    }
 }
 
 fn absolute_difference(a: u8, b: u8) -> u8 {
     (a as i16 - b as i16).abs() as u8
 }
 
-fn draw_reticle<T>(interface: &mut T, sight: &Sight)
+fn draw_reticle<T>(interface: &mut T, sight: &Sight, custom_reticle: &CustomReticle)
 where
     T: DrawTarget<Color = Rgb565, Error: Debug>,
 {
-    let reticle_size: u8 = 8;
-    let position_x = (128 / 2 + sight.x_zero) as u8;
-    let position_y = (96 / 2 + sight.y_zero) as u8;
-    let r = Rectangle::new(
-        Point::new(
-            (position_x - reticle_size / 2) as i32,
-            (position_y - reticle_size / 2) as i32,
-        ),
-        Size::new(reticle_size as u32, reticle_size as u32),
-    )
-    .into_styled(
-        PrimitiveStyleBuilder::new()
-            .stroke_width(1)
-            .stroke_color(Rgb565::RED)
-            .build(),
-    );
-    r.draw(interface).unwrap();
-    let adjusted = Rectangle::new(
-        Point::new(
-            (position_x - reticle_size / 4) as i32,
-            (position_y - reticle_size / 4 + sight.range / 2) as i32,
-        ),
-        Size::new((reticle_size / 2) as u32, (reticle_size / 2) as u32),
-    )
-    .into_styled(
-        PrimitiveStyleBuilder::new()
-            .stroke_width(1)
-            .stroke_color(Rgb565::RED)
-            .build(),
-    );
-    adjusted.draw(interface).unwrap();
+    let (zero_x, zero_y) = sight.zero_offset();
+    let position_x = Fixed::from_int(128 / 2) + zero_x;
+    let position_y = Fixed::from_int(96 / 2) + zero_y;
+    // Out of reach the reticle stays at the zero, the HUD flags the range.
+    let windage = sight.windage().unwrap_or(Holdover::NONE);
+    let holdover = sight.holdover().unwrap_or(Holdover::NONE);
+    let holdover_x =
+        (position_x + windage.offset(sight.pixel_scale)).clamp(Fixed::ZERO, Fixed::from_int(128));
+    let holdover_y =
+        (position_y + holdover.offset(sight.pixel_scale)).clamp(Fixed::ZERO, Fixed::from_int(96));
+    let aim_point = if sight.reticle.is_fixed() {
+        (position_x, position_y)
+    } else {
+        (holdover_x, holdover_y)
+    };
+    let color = sight.reticle_color().rgb();
+    if sight.reticle.kind == ReticleKind::BdcLadder {
+        // Too tall for the subpixel cache, it would work out the marks and
+        // render the labels again for every row, so it snaps to a pixel.
+        let zero = Point::new(aim_point.0.round(), aim_point.1.round());
+        sight
+            .reticle
+            .draw_ladder(interface, zero, color, sight.ladder_marks());
+    } else {
+        // Drawn at a fractional position, so zero clicks and holdovers
+        // smaller than a pixel still move the reticle.
+        subpixel::draw(interface, aim_point, color, |probe, aim_point| {
+            sight.reticle.draw(probe, aim_point, color, custom_reticle);
+        });
+    }
 
+    if let Some(lead) = sight.lead_offset() {
+        let lead_point = Point::new(
+            (holdover_x.round() + lead.pixels(sight.pixel_scale) as i32).clamp(0, 128),
+            holdover_y.round(),
+        );
+        let lead_color = if sight.night_vision {
+            color
+        } else {
+            Rgb565::YELLOW
+        };
+        let style = PrimitiveStyle::with_stroke(lead_color, 1);
+        Line::new(lead_point - Point::new(2, 0), lead_point + Point::new(2, 0))
+            .into_styled(style)
+            .draw(interface)
+            .unwrap();
+        Line::new(lead_point - Point::new(0, 2), lead_point + Point::new(0, 2))
+            .into_styled(style)
+            .draw(interface)
+            .unwrap();
+    }
+
+    if sight.night_vision {
+        return;
+    }
     Rectangle::new(Point::new(0, 0), interface.bounding_box().size)
         .into_styled(
             PrimitiveStyleBuilder::new()
@@ -162,10 +463,24 @@ fn write_value<T>(interface: &mut T, value: u8, position: Point, buffer: &mut [u
 where
     T: DrawTarget<Color = Rgb565, Error: Debug>,
 {
-    let style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
-
     format_two_digit(value, buffer);
-    Text::new(unsafe { str::from_utf8_unchecked(&buffer) }, position, style)
+    write_text(interface, buffer, position);
+}
+
+fn write_text<T>(interface: &mut T, buffer: &[u8], position: Point)
+where
+    T: DrawTarget<Color = Rgb565, Error: Debug>,
+{
+    write_colored_text(interface, buffer, position, Rgb565::WHITE);
+}
+
+fn write_colored_text<T>(interface: &mut T, buffer: &[u8], position: Point, color: Rgb565)
+where
+    T: DrawTarget<Color = Rgb565, Error: Debug>,
+{
+    let style = MonoTextStyle::new(&FONT_6X10, color);
+
+    Text::new(unsafe { str::from_utf8_unchecked(buffer) }, position, style)
         .draw(interface)
         .unwrap();
 }
@@ -178,16 +493,3 @@ fn format_two_digit(num: u8, buf: &mut [u8]) {
     buf[len - 2] = b'0' + ((num / 10) % 10) as u8;
     buf[len - 1] = b'0' + (num % 10) as u8;
 }
-
-fn format_two_digit_16(num: i16, buf: &mut [u8]) {
-    assert!(buf.len() >= 4, "Buffer must be at least 4 bytes long");
-
-
-    let len = buf.len();
-    let abs_num = num.abs() as u16; 
-
-    buf[len - 4] = if num < 0 { b'-' } else { b' ' };
-    buf[len - 3] = b'0' + ((abs_num / 100) % 10) as u8;
-    buf[len - 2] = b'0' + ((abs_num / 10) % 10) as u8;
-    buf[len - 1] = b'0' + (abs_num % 10) as u8;
-}
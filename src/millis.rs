@@ -0,0 +1,32 @@
+use core::cell::Cell;
+
+use avr_device::interrupt::Mutex;
+
+/// 16 MHz divided by 64 ticks 250 times a millisecond.
+const PRESCALER: u32 = 64;
+const TIMER_COUNTS: u32 = 250;
+
+static MILLIS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+/// Starts TC0 counting milliseconds, interrupts have to be enabled after.
+pub fn init(tc0: arduino_hal::pac::TC0) {
+    const _: () = assert!(arduino_hal::DefaultClock::FREQ / PRESCALER / TIMER_COUNTS == 1000);
+    tc0.tccr0a().write(|w| w.wgm0().ctc());
+    tc0.ocr0a().write(|w| w.set((TIMER_COUNTS - 1) as u8));
+    tc0.tccr0b().write(|w| w.cs0().prescale_64());
+    tc0.timsk0().write(|w| w.ocie0a().set_bit());
+    avr_device::interrupt::free(|cs| MILLIS.borrow(cs).set(0));
+}
+
+#[avr_device::interrupt(atmega328p)]
+fn TIMER0_COMPA() {
+    avr_device::interrupt::free(|cs| {
+        let millis = MILLIS.borrow(cs);
+        millis.set(millis.get().wrapping_add(1));
+    });
+}
+
+/// Milliseconds since `init`, wrapping after about 49 days.
+pub fn now() -> u32 {
+    avr_device::interrupt::free(|cs| MILLIS.borrow(cs).get())
+}
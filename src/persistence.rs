@@ -0,0 +1,412 @@
+//! Settings kept in the EEPROM across power cycles. Every record validates
+//! itself on load, so a blank or stale EEPROM falls back to the defaults.
+//!
+//! Each record type has a `to_bytes` giving its fixed-size layout and a
+//! `from_bytes` that reads it back, returning `None` for bytes that are not a
+//! valid record, such as the `0xFF`s of an erased EEPROM. The uploaded
+//! reticle is the exception, its `as_record` borrows it to avoid a copy.
+
+use crate::{
+    appearance::{Appearance, APPEARANCE_BYTES},
+    ballistics::{
+        ammo::{AMMO_NAMES, USER_AMMO_BYTES, USER_AMMO_SLOTS},
+        DropTable, PixelScale, DROP_TABLE_BYTES, MAX_HOP_STRENGTH, PIXEL_SCALE_BYTES,
+    },
+    reticle::{CustomReticle, Reticle, CUSTOM_RETICLE_BYTES, RETICLE_BYTES},
+    sight::{
+        DropSource, LadderSpacing, Sight, LADDER_SPACING_BYTES, MAX_CANT_THRESHOLD,
+        MAX_CHRONO_VELOCITY, MAX_ZERO_RANGE, MIN_CANT_THRESHOLD, MIN_ZERO_RANGE,
+    },
+    units::{Units, UNITS_BYTES},
+};
+
+/// `Reticle::to_bytes`.
+const RETICLE_ADDRESS: u16 = 0;
+/// `Appearance::to_bytes`.
+const APPEARANCE_ADDRESS: u16 = RETICLE_ADDRESS + RETICLE_BYTES as u16;
+/// `CustomReticle::as_record`.
+const CUSTOM_RETICLE_ADDRESS: u16 = APPEARANCE_ADDRESS + APPEARANCE_BYTES as u16;
+/// `PixelScale::to_bytes`.
+const PIXEL_SCALE_ADDRESS: u16 = CUSTOM_RETICLE_ADDRESS + CUSTOM_RETICLE_BYTES as u16;
+/// `LadderSpacing::to_bytes`.
+const LADDER_SPACING_ADDRESS: u16 = PIXEL_SCALE_ADDRESS + PIXEL_SCALE_BYTES as u16;
+/// `Sight::chrono_velocity`, one byte per profile.
+const CHRONO_VELOCITY_ADDRESS: u16 = LADDER_SPACING_ADDRESS + LADDER_SPACING_BYTES as u16;
+/// `Sight::drop_source`, one byte.
+const DROP_SOURCE_ADDRESS: u16 = CHRONO_VELOCITY_ADDRESS + AMMO_NAMES.len() as u16;
+/// `DropTable::to_bytes`.
+const DROP_TABLE_ADDRESS: u16 = DROP_SOURCE_ADDRESS + 1;
+/// `Sight::ammo_index`, one byte.
+const AMMO_INDEX_ADDRESS: u16 = DROP_TABLE_ADDRESS + DROP_TABLE_BYTES as u16;
+/// `AmmoProfile::to_bytes` of each of `Sight::user_ammo`.
+const USER_AMMO_ADDRESS: u16 = AMMO_INDEX_ADDRESS + 1;
+/// `Sight::zero_range`, one byte.
+const ZERO_RANGE_ADDRESS: u16 = user_ammo_address(USER_AMMO_SLOTS);
+/// `Sight::hop_strength`, one byte.
+const HOP_STRENGTH_ADDRESS: u16 = ZERO_RANGE_ADDRESS + 1;
+/// `Units::to_bytes`.
+const UNITS_ADDRESS: u16 = HOP_STRENGTH_ADDRESS + 1;
+/// `Sight::cant_threshold_deg`, one byte.
+const CANT_THRESHOLD_ADDRESS: u16 = UNITS_ADDRESS + UNITS_BYTES as u16;
+/// `Sight::readouts`, one byte.
+const READOUTS_ADDRESS: u16 = CANT_THRESHOLD_ADDRESS + 1;
+/// First byte past the records, which have to fit the ATmega328P's 1 KiB.
+const END_ADDRESS: u16 = READOUTS_ADDRESS + 1;
+const _: () = assert!(END_ADDRESS <= 1024);
+
+/// Byte-addressed non-volatile memory.
+pub trait Storage {
+    fn read(&mut self, address: u16, buffer: &mut [u8]);
+    fn write(&mut self, address: u16, data: &[u8]);
+}
+
+/// Restores the persisted parts of `sight`.
+pub fn load(storage: &mut impl Storage, sight: &mut Sight) {
+    let mut reticle = [0; RETICLE_BYTES];
+    storage.read(RETICLE_ADDRESS, &mut reticle);
+    if let Some(reticle) = Reticle::from_bytes(&reticle) {
+        sight.reticle = reticle;
+    }
+    let mut pixel_scale = [0; PIXEL_SCALE_BYTES];
+    storage.read(PIXEL_SCALE_ADDRESS, &mut pixel_scale);
+    if let Some(pixel_scale) = PixelScale::from_bytes(&pixel_scale) {
+        sight.pixel_scale = pixel_scale;
+    }
+    let mut ladder = [0; LADDER_SPACING_BYTES];
+    storage.read(LADDER_SPACING_ADDRESS, &mut ladder);
+    if let Some(ladder) = LadderSpacing::from_bytes(&ladder) {
+        sight.ladder = ladder;
+    }
+    let mut ammo_index = [0];
+    storage.read(AMMO_INDEX_ADDRESS, &mut ammo_index);
+    if (ammo_index[0] as usize) < AMMO_NAMES.len() {
+        sight.ammo_index = ammo_index[0];
+    }
+    for (slot, ammo) in sight.user_ammo.iter_mut().enumerate() {
+        let mut bytes = [0; USER_AMMO_BYTES];
+        storage.read(user_ammo_address(slot), &mut bytes);
+        if let Some(restored) = ammo.with_bytes(&bytes) {
+            *ammo = restored;
+        }
+    }
+    let mut zero_range = [0];
+    storage.read(ZERO_RANGE_ADDRESS, &mut zero_range);
+    if (MIN_ZERO_RANGE..=MAX_ZERO_RANGE).contains(&zero_range[0]) {
+        sight.zero_range = zero_range[0];
+    }
+    let mut hop_strength = [0];
+    storage.read(HOP_STRENGTH_ADDRESS, &mut hop_strength);
+    if hop_strength[0] <= MAX_HOP_STRENGTH {
+        sight.hop_strength = hop_strength[0];
+    }
+    let mut chrono_velocity = [0; AMMO_NAMES.len()];
+    storage.read(CHRONO_VELOCITY_ADDRESS, &mut chrono_velocity);
+    if chrono_velocity
+        .iter()
+        .all(|velocity| *velocity <= MAX_CHRONO_VELOCITY)
+    {
+        sight.chrono_velocity = chrono_velocity;
+    }
+    let mut drop_source = [0];
+    storage.read(DROP_SOURCE_ADDRESS, &mut drop_source);
+    let mut drop_table = [0; DROP_TABLE_BYTES];
+    storage.read(DROP_TABLE_ADDRESS, &mut drop_table);
+    // An erased table reads as -1 cm throughout, so the source saved with it
+    // tells whether there is one.
+    let drop_source = match drop_source {
+        [0] => Some(DropSource::Model),
+        [1] => Some(DropSource::Measured),
+        _ => None,
+    };
+    let drop_table = DropTable::from_bytes(&drop_table);
+    if let (Some(drop_source), Some(drop_table)) = (drop_source, drop_table) {
+        sight.drop_source = drop_source;
+        sight.drop_table = drop_table;
+    }
+    let mut units = [0; UNITS_BYTES];
+    storage.read(UNITS_ADDRESS, &mut units);
+    if let Some(units) = Units::from_bytes(&units) {
+        sight.units = units;
+    }
+    let mut cant_threshold = [0];
+    storage.read(CANT_THRESHOLD_ADDRESS, &mut cant_threshold);
+    if (MIN_CANT_THRESHOLD..=MAX_CANT_THRESHOLD).contains(&cant_threshold[0]) {
+        sight.cant_threshold_deg = cant_threshold[0];
+    }
+    let mut readouts = [0];
+    storage.read(READOUTS_ADDRESS, &mut readouts);
+    if readouts[0] < 1 << 2 {
+        sight.set_readouts(readouts[0]);
+    }
+    let mut appearance = [0; APPEARANCE_BYTES];
+    storage.read(APPEARANCE_ADDRESS, &mut appearance);
+    if let Some(appearance) = Appearance::from_bytes(&appearance) {
+        sight.appearance = appearance;
+    }
+    // The restored profile, velocities, hop and zero call for their own
+    // table.
+    sight.refresh_holdover();
+}
+
+/// Writes the persisted parts of `sight`, skipping records that did not
+/// change to spare the EEPROM.
+pub fn save(storage: &mut impl Storage, sight: &Sight) {
+    write_if_changed(storage, RETICLE_ADDRESS, &sight.reticle.to_bytes());
+    write_if_changed(storage, APPEARANCE_ADDRESS, &sight.appearance.to_bytes());
+    write_if_changed(storage, PIXEL_SCALE_ADDRESS, &sight.pixel_scale.to_bytes());
+    write_if_changed(storage, LADDER_SPACING_ADDRESS, &sight.ladder.to_bytes());
+    write_if_changed(storage, CHRONO_VELOCITY_ADDRESS, &sight.chrono_velocity);
+    write_if_changed(storage, DROP_SOURCE_ADDRESS, &[sight.drop_source as u8]);
+    write_if_changed(storage, DROP_TABLE_ADDRESS, &sight.drop_table.to_bytes());
+    write_if_changed(storage, AMMO_INDEX_ADDRESS, &[sight.ammo_index]);
+    for (slot, ammo) in sight.user_ammo.iter().enumerate() {
+        write_if_changed(storage, user_ammo_address(slot), &ammo.to_bytes());
+    }
+    write_if_changed(storage, ZERO_RANGE_ADDRESS, &[sight.zero_range]);
+    write_if_changed(storage, HOP_STRENGTH_ADDRESS, &[sight.hop_strength]);
+    write_if_changed(storage, UNITS_ADDRESS, &sight.units.to_bytes());
+    write_if_changed(storage, CANT_THRESHOLD_ADDRESS, &[sight.cant_threshold_deg]);
+    write_if_changed(storage, READOUTS_ADDRESS, &[sight.readouts()]);
+}
+
+/// Restores the uploaded reticle, `CustomReticle::EMPTY` if there is none.
+pub fn load_custom_reticle(storage: &mut impl Storage) -> CustomReticle {
+    let mut custom = [0; CUSTOM_RETICLE_BYTES];
+    storage.read(CUSTOM_RETICLE_ADDRESS, &mut custom);
+    CustomReticle::from_bytes(&custom).unwrap_or(CustomReticle::EMPTY)
+}
+
+/// Writes the uploaded reticle if it changed.
+pub fn save_custom_reticle(storage: &mut impl Storage, custom: &CustomReticle) {
+    write_if_changed(storage, CUSTOM_RETICLE_ADDRESS, &custom.as_record());
+}
+
+const fn user_ammo_address(slot: usize) -> u16 {
+    USER_AMMO_ADDRESS + (slot * USER_AMMO_BYTES) as u16
+}
+
+fn write_if_changed<const N: usize>(storage: &mut impl Storage, address: u16, data: &[u8; N]) {
+    let mut stored = [0; N];
+    storage.read(address, &mut stored);
+    if stored != *data {
+        storage.write(address, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ballistics::Holdover,
+        fixed::Fixed,
+        reticle::CustomFormat,
+        units::{RangeUnit, VelocityUnit},
+    };
+
+    /// An EEPROM in memory, erased to `0xFF` like a new chip.
+    struct MemoryStorage([u8; 1024]);
+
+    impl Storage for MemoryStorage {
+        fn read(&mut self, address: u16, buffer: &mut [u8]) {
+            let start = address as usize;
+            buffer.copy_from_slice(&self.0[start..start + buffer.len()]);
+        }
+
+        fn write(&mut self, address: u16, data: &[u8]) {
+            let start = address as usize;
+            self.0[start..start + data.len()].copy_from_slice(data);
+        }
+    }
+
+    #[test]
+    fn blank_storage_keeps_the_defaults() {
+        let mut sight = Sight::new();
+        load(&mut MemoryStorage([0xFF; 1024]), &mut sight);
+        assert!(sight == Sight::new());
+    }
+
+    #[test]
+    fn restores_the_ladder_spacing() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.ladder = LadderSpacing { step: 5, max: 40 };
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert!(restored.ladder == sight.ladder);
+    }
+
+    #[test]
+    fn restores_the_chrono_velocities() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.ammo_index = 1;
+        sight.set_chrono_velocity(93);
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert_eq!(restored.chrono_velocity, sight.chrono_velocity);
+        restored.ammo_index = 1;
+        assert!(restored.ammo().muzzle_velocity == Fixed::from_int(93));
+    }
+
+    #[test]
+    fn restores_the_measured_drops() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.drop_source = DropSource::Measured;
+        sight.drop_table.impacts_cm = [2, 8, 18, 32, -300];
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert!(restored.drop_source == DropSource::Measured);
+        assert!(restored.drop_table == sight.drop_table);
+    }
+
+    #[test]
+    fn ignores_out_of_range_drops() {
+        let mut table = DropTable::EMPTY;
+        table.impacts_cm[2] = 301;
+        assert!(DropTable::from_bytes(&table.to_bytes()).is_none());
+        table.impacts_cm[2] = -300;
+        assert!(DropTable::from_bytes(&table.to_bytes()) == Some(table));
+    }
+
+    #[test]
+    fn restores_the_selected_and_user_ammo() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.ammo_index = (AMMO_NAMES.len() - 1) as u8;
+        let user = sight.user_ammo_mut().unwrap();
+        user.mass_g = Fixed::from_ratio(28, 100);
+        user.diameter_mm = Fixed::from_ratio(593, 100);
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert_eq!(restored.ammo_index, sight.ammo_index);
+        assert!(restored.ammo() == sight.ammo());
+        assert!(restored.user_ammo[0] == sight.user_ammo[0]);
+        assert!(restored.holdover_table.is_for(
+            &sight.ammo(),
+            restored.hop_strength,
+            restored.atmosphere.density()
+        ));
+    }
+
+    #[test]
+    fn restores_the_zero_range() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.zero_range = 20;
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert_eq!(restored.zero_range, 20);
+        restored.range = 20;
+        assert_eq!(restored.holdover(), Some(Holdover::NONE));
+        storage.write(ZERO_RANGE_ADDRESS, &[MAX_ZERO_RANGE + 1]);
+        let mut sight = Sight::new();
+        load(&mut storage, &mut sight);
+        assert_eq!(sight.zero_range, Sight::new().zero_range);
+    }
+
+    #[test]
+    fn restores_the_hop_strength() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.hop_strength = MAX_HOP_STRENGTH;
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert_eq!(restored.hop_strength, MAX_HOP_STRENGTH);
+        assert!(restored.holdover_table.is_for(
+            &restored.ammo(),
+            MAX_HOP_STRENGTH,
+            restored.atmosphere.density()
+        ));
+        storage.write(HOP_STRENGTH_ADDRESS, &[MAX_HOP_STRENGTH + 1]);
+        let mut sight = Sight::new();
+        load(&mut storage, &mut sight);
+        assert_eq!(sight.hop_strength, Sight::new().hop_strength);
+    }
+
+    #[test]
+    fn restores_the_units() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.units.range = RangeUnit::Yards;
+        sight.units.velocity = VelocityUnit::FeetPerSecond;
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert!(restored.units == sight.units);
+    }
+
+    #[test]
+    fn restores_the_cant_threshold() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.cant_threshold_deg = MAX_CANT_THRESHOLD;
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert_eq!(restored.cant_threshold_deg, MAX_CANT_THRESHOLD);
+        storage.write(CANT_THRESHOLD_ADDRESS, &[0]);
+        let mut sight = Sight::new();
+        load(&mut storage, &mut sight);
+        assert_eq!(sight.cant_threshold_deg, Sight::new().cant_threshold_deg);
+    }
+
+    #[test]
+    fn restores_the_hud_readouts() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut sight = Sight::new();
+        sight.show_energy = true;
+        save(&mut storage, &sight);
+        let mut restored = Sight::new();
+        load(&mut storage, &mut restored);
+        assert!(!restored.show_flight_time && restored.show_energy);
+    }
+
+    #[test]
+    fn ignores_out_of_range_ammo() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        storage.write(AMMO_INDEX_ADDRESS, &[AMMO_NAMES.len() as u8]);
+        storage.write(USER_AMMO_ADDRESS, &[60, 0x52, 0x02]);
+        let mut sight = Sight::new();
+        load(&mut storage, &mut sight);
+        assert!(sight == Sight::new());
+    }
+
+    #[test]
+    fn ignores_out_of_range_chrono_velocities() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        let mut chrono_velocity = [0; AMMO_NAMES.len()];
+        chrono_velocity[0] = MAX_CHRONO_VELOCITY + 1;
+        storage.write(CHRONO_VELOCITY_ADDRESS, &chrono_velocity);
+        let mut sight = Sight::new();
+        load(&mut storage, &mut sight);
+        assert_eq!(sight.chrono_velocity, [0; AMMO_NAMES.len()]);
+    }
+
+    #[test]
+    fn restores_the_uploaded_reticle() {
+        let mut storage = MemoryStorage([0xFF; 1024]);
+        assert!(load_custom_reticle(&mut storage) == CustomReticle::EMPTY);
+        let cross = CustomReticle::new(CustomFormat::Bitmap, &[3, 3, 0xA0, 0x40, 0xA0]).unwrap();
+        save_custom_reticle(&mut storage, &cross);
+        assert!(load_custom_reticle(&mut storage) == cross);
+        // The rest of the settings are not in the way.
+        save(&mut storage, &Sight::new());
+        assert!(load_custom_reticle(&mut storage) == cross);
+    }
+
+    #[test]
+    fn ignores_out_of_range_ladder_spacings() {
+        for bytes in [[0, 60], [25, 60], [10, 5], [10, 90]] {
+            assert!(LadderSpacing::from_bytes(&bytes).is_none());
+        }
+        let default = LadderSpacing::DEFAULT;
+        assert!(LadderSpacing::from_bytes(&default.to_bytes()) == Some(default));
+    }
+}
@@ -0,0 +1,158 @@
+/// Parses distances streamed by a laser rangefinder module over the serial
+/// port.
+///
+/// Low-cost modules print one reading per line, a label, a decimal distance in
+/// metres and an optional signal quality after a comma: `D=12.345m`,
+/// `Dist: 12.345 m` or `D=12.3m,0079`. The label and the unit are required,
+/// so a reading joined half way, with the start of the line missing, and
+/// chronograph readings on the same port are not mistaken for distances.
+/// Lines that do not match, such as error reports, are ignored.
+pub struct RangefinderParser {
+    state: State,
+    /// Distance read so far, in tenths of a metre.
+    tenths: u32,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum State {
+    /// At the start of a line.
+    Start,
+    /// In the label, before its `=` or `:`.
+    Label,
+    /// After the label, before the distance.
+    Separator,
+    Whole,
+    Fraction {
+        digits: u8,
+    },
+    /// After the `m` unit.
+    Unit,
+    /// After the comma, skipping the signal quality.
+    Quality,
+    /// Skipping until the end of a malformed line.
+    Invalid,
+}
+
+impl RangefinderParser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Start,
+            tenths: 0,
+        }
+    }
+
+    /// Feeds one received byte, returns the distance in whole metres once a
+    /// complete reading has been parsed. Distances past 255 m saturate.
+    pub fn push(&mut self, byte: u8) -> Option<u8> {
+        if byte == b'\r' || byte == b'\n' {
+            let range = self.finish();
+            self.reset();
+            return range;
+        }
+        self.state = match (self.state, byte) {
+            (State::Start | State::Label, b'a'..=b'z' | b'A'..=b'Z') => State::Label,
+            (State::Label, b' ') => State::Label,
+            (State::Label, b'=' | b':') => State::Separator,
+            (State::Separator, b' ') => State::Separator,
+            (State::Separator | State::Whole, b'0'..=b'9') => {
+                self.tenths = self.tenths * 10 + (byte - b'0') as u32 * 10;
+                State::Whole
+            }
+            (State::Whole, b'.') => State::Fraction { digits: 0 },
+            (State::Fraction { digits: 0 }, b'0'..=b'9') => {
+                self.tenths += (byte - b'0') as u32;
+                State::Fraction { digits: 1 }
+            }
+            // Centimetres and millimetres are not needed.
+            (State::Fraction { digits }, b'0'..=b'9') => State::Fraction { digits },
+            (State::Whole | State::Fraction { .. }, b'm' | b'M') => State::Unit,
+            (State::Whole | State::Fraction { .. }, b' ') => self.state,
+            (State::Unit, b',') => State::Quality,
+            (State::Quality, _) => State::Quality,
+            _ => State::Invalid,
+        };
+        // Guard against overflow from absurdly long numbers.
+        if self.tenths > 100_000 {
+            self.state = State::Invalid;
+        }
+        None
+    }
+
+    fn finish(&self) -> Option<u8> {
+        match self.state {
+            State::Unit | State::Quality => {
+                Some(((self.tenths + 5) / 10).min(u8::MAX as u32) as u8)
+            }
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(parser: &mut RangefinderParser, stream: &[u8]) -> Vec<u8> {
+        stream
+            .iter()
+            .filter_map(|byte| parser.push(*byte))
+            .collect()
+    }
+
+    fn parse(stream: &[u8]) -> Vec<u8> {
+        feed(&mut RangefinderParser::new(), stream)
+    }
+
+    #[test]
+    fn reads_each_line_format() {
+        let stream = b"D=23.7m\r\nDist: 8.04 m\r\nD=41.2m,0079\r\n";
+        assert_eq!(parse(stream), [24, 8, 41]);
+    }
+
+    #[test]
+    fn reads_frames_split_across_pushes() {
+        let mut parser = RangefinderParser::new();
+        assert!(feed(&mut parser, b"D=3").is_empty());
+        assert_eq!(feed(&mut parser, b"5.61m\r\n"), [36]);
+    }
+
+    #[test]
+    fn skips_a_frame_joined_half_way() {
+        // Started listening in the middle of `D=12.345m`.
+        assert_eq!(parse(b".345m\r\nD=15.000m\r\n"), [15]);
+        assert_eq!(parse(b"45m,0079\r\nD=15.000m\r\n"), [15]);
+        assert_eq!(parse(b"=12.345m\r\nD=15.000m\r\n"), [15]);
+    }
+
+    #[test]
+    fn ignores_unlabelled_lines() {
+        assert!(parse(b"12.345m\r\n41.2m,0079\r\n").is_empty());
+    }
+
+    #[test]
+    fn waits_for_the_end_of_the_line() {
+        assert!(parse(b"D=12.345m").is_empty());
+        assert!(parse(b"D=12.345m,00").is_empty());
+    }
+
+    #[test]
+    fn ignores_corrupt_frames() {
+        let stream = b"D=12.3?5m\r\nERR-255\r\n12.3\r\nm\r\n\xff\xfe\r\nD=1.2.3m\r\nD=20.0m\r\n";
+        assert_eq!(parse(stream), [20]);
+    }
+
+    #[test]
+    fn ignores_chronograph_readings() {
+        assert!(parse(b"95.3\r\n95.3 m/s\r\n312 fps\r\n").is_empty());
+    }
+
+    #[test]
+    fn saturates_long_distances() {
+        assert_eq!(parse(b"D=300.0m\r\n"), [255]);
+        assert!(parse(b"D=123456789m\r\n").is_empty());
+    }
+}
@@ -0,0 +1,446 @@
+use core::fmt::Debug;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_4X6, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Pixel, Point, Primitive},
+    primitives::{Circle, Line, PrimitiveStyle},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use crate::fixed::Fixed;
+
+/// Built-in reticle patterns.
+#[derive(PartialEq, Clone, Copy)]
+pub enum ReticleKind {
+    Dot,
+    Crosshair,
+    CircleDot,
+    Chevron,
+    TPost,
+    /// Vertical ladder with labelled hash marks at the holdovers for a set
+    /// of ranges. It stays at the zero, the shooter picks the mark instead
+    /// of the reticle moving.
+    BdcLadder,
+    /// Pattern uploaded over the serial port, see `CustomReticle`.
+    Custom,
+}
+
+pub const RETICLE_KINDS: [ReticleKind; 7] = [
+    ReticleKind::Dot,
+    ReticleKind::Crosshair,
+    ReticleKind::CircleDot,
+    ReticleKind::Chevron,
+    ReticleKind::TPost,
+    ReticleKind::BdcLadder,
+    ReticleKind::Custom,
+];
+
+/// Labels of `RETICLE_KINDS`, for choosing one in the settings.
+pub const RETICLE_NAMES: [&str; RETICLE_KINDS.len()] = [
+    "Dot",
+    "Crosshair",
+    "Circle Dot",
+    "Chevron",
+    "T-Post",
+    "BDC Ladder",
+    "Custom",
+];
+
+/// One hash mark of the BDC ladder.
+pub struct LadderMark {
+    /// Range shown next to the mark, in the user's units.
+    pub label: i16,
+    /// Distance below the zero in pixels.
+    pub offset_px: i16,
+}
+
+pub const MIN_SIZE: u8 = 1;
+pub const MAX_SIZE: u8 = 40;
+pub const MIN_THICKNESS: u8 = 1;
+pub const MAX_THICKNESS: u8 = 4;
+
+/// Dimensions of one reticle pattern in pixels. What `size` measures depends
+/// on the pattern: the dot diameter, the circle diameter, the length of the
+/// arms or post, or the width of the ladder's hash marks. Custom reticles
+/// keep their uploaded size and only use the thickness.
+#[derive(PartialEq, Clone, Copy)]
+pub struct ReticleParams {
+    pub size: u8,
+    pub thickness: u8,
+}
+
+/// The chosen pattern, with each pattern keeping its own dimensions.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Reticle {
+    pub kind: ReticleKind,
+    pub params: [ReticleParams; RETICLE_KINDS.len()],
+}
+
+/// Bytes `Reticle::to_bytes` produces.
+pub const RETICLE_BYTES: usize = 1 + 2 * RETICLE_KINDS.len();
+
+impl Reticle {
+    pub const DEFAULT: Reticle = Reticle {
+        kind: ReticleKind::Crosshair,
+        params: [
+            ReticleParams {
+                size: 3,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 8,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 12,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 5,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 10,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 3,
+                thickness: 1,
+            },
+            ReticleParams {
+                size: 1,
+                thickness: 1,
+            },
+        ],
+    };
+
+    pub fn index(&self) -> usize {
+        self.kind as usize
+    }
+
+    /// Dimensions of the chosen pattern.
+    pub fn selected(&self) -> ReticleParams {
+        self.params[self.index()]
+    }
+
+    pub fn selected_mut(&mut self) -> &mut ReticleParams {
+        &mut self.params[self.index()]
+    }
+
+    /// Whether the pattern stays at the zero instead of following the
+    /// holdover.
+    pub fn is_fixed(&self) -> bool {
+        self.kind == ReticleKind::BdcLadder
+    }
+
+    /// Draws the chosen pattern with its aim point at `center`, `custom`
+    /// being the uploaded one.
+    pub fn draw<T>(&self, target: &mut T, center: Point, color: Rgb565, custom: &CustomReticle)
+    where
+        T: DrawTarget<Color = Rgb565, Error: Debug>,
+    {
+        let ReticleParams { size, thickness } = self.selected();
+        let size = size as i32;
+        let stroke = PrimitiveStyle::with_stroke(color, thickness as u32);
+        let fill = PrimitiveStyle::with_fill(color);
+        let mut line = |from: Point, to: Point| {
+            Line::new(center + from, center + to)
+                .into_styled(stroke)
+                .draw(target)
+                .unwrap();
+        };
+        match self.kind {
+            ReticleKind::Dot => {
+                Circle::with_center(center, size as u32)
+                    .into_styled(fill)
+                    .draw(target)
+                    .unwrap();
+            }
+            ReticleKind::Crosshair => {
+                line(Point::new(-size, 0), Point::new(size, 0));
+                line(Point::new(0, -size), Point::new(0, size));
+            }
+            ReticleKind::CircleDot => {
+                Circle::with_center(center, size as u32)
+                    .into_styled(stroke)
+                    .draw(target)
+                    .unwrap();
+                Circle::with_center(center, thickness as u32 + 1)
+                    .into_styled(fill)
+                    .draw(target)
+                    .unwrap();
+            }
+            ReticleKind::Chevron => {
+                line(Point::zero(), Point::new(-size, size));
+                line(Point::zero(), Point::new(size, size));
+            }
+            ReticleKind::TPost => {
+                line(Point::new(-size, 0), Point::new(size, 0));
+                line(Point::new(0, 1), Point::new(0, size));
+            }
+            // Only the mark at the zero, `draw_ladder` adds the rest.
+            ReticleKind::BdcLadder => line(Point::new(-size * 2, 0), Point::new(size * 2, 0)),
+            ReticleKind::Custom => custom.draw(target, center, color, thickness),
+        }
+    }
+
+    /// Draws the BDC ladder hanging from `zero`, with a hash mark and a
+    /// range label for each of `marks`.
+    pub fn draw_ladder<T>(
+        &self,
+        target: &mut T,
+        zero: Point,
+        color: Rgb565,
+        marks: impl Iterator<Item = LadderMark>,
+    ) where
+        T: DrawTarget<Color = Rgb565, Error: Debug>,
+    {
+        let ReticleParams { size, thickness } = self.selected();
+        let size = size as i32;
+        let stroke = PrimitiveStyle::with_stroke(color, thickness as u32);
+        let label_style = MonoTextStyle::new(&FONT_4X6, color);
+        let (mut top, mut bottom) = (zero.y, zero.y);
+        for mark in marks {
+            let y = zero.y + mark.offset_px as i32;
+            top = top.min(y);
+            bottom = bottom.max(y);
+            Line::new(Point::new(zero.x - size, y), Point::new(zero.x + size, y))
+                .into_styled(stroke)
+                .draw(target)
+                .unwrap();
+            let mut label = [b' '; 3];
+            Fixed::from(mark.label).format(0, &mut label);
+            let start = label.iter().position(|byte| *byte != b' ').unwrap_or(0);
+            Text::with_baseline(
+                unsafe { str::from_utf8_unchecked(&label[start..]) },
+                Point::new(zero.x + size + 2, y),
+                label_style,
+                Baseline::Middle,
+            )
+            .draw(target)
+            .unwrap();
+        }
+        Line::new(Point::new(zero.x, top), Point::new(zero.x, bottom))
+            .into_styled(stroke)
+            .draw(target)
+            .unwrap();
+        // The ladder is never the uploaded pattern.
+        self.draw(target, zero, color, &CustomReticle::EMPTY);
+    }
+
+    /// Pattern and sizes as stored in the EEPROM.
+    pub fn to_bytes(self) -> [u8; RETICLE_BYTES] {
+        let mut bytes = [0; RETICLE_BYTES];
+        bytes[0] = self.kind as u8;
+        for (index, params) in self.params.iter().enumerate() {
+            bytes[1 + 2 * index] = params.size;
+            bytes[2 + 2 * index] = params.thickness;
+        }
+        bytes
+    }
+
+    /// Reticle stored by `to_bytes`, if the pattern and sizes are valid.
+    pub fn from_bytes(bytes: &[u8; RETICLE_BYTES]) -> Option<Self> {
+        let mut reticle = Reticle::DEFAULT;
+        reticle.kind = *RETICLE_KINDS.get(bytes[0] as usize)?;
+        for (index, params) in reticle.params.iter_mut().enumerate() {
+            let (size, thickness) = (bytes[1 + 2 * index], bytes[2 + 2 * index]);
+            if !(MIN_SIZE..=MAX_SIZE).contains(&size)
+                || !(MIN_THICKNESS..=MAX_THICKNESS).contains(&thickness)
+            {
+                return None;
+            }
+            *params = ReticleParams { size, thickness };
+        }
+        Some(reticle)
+    }
+}
+
+/// Largest payload of a custom reticle.
+pub const CUSTOM_PAYLOAD_BYTES: usize = 74;
+/// Bytes `CustomReticle::as_record` produces.
+pub const CUSTOM_RETICLE_BYTES: usize = 2 + CUSTOM_PAYLOAD_BYTES;
+/// Largest width or height of a bitmap reticle in pixels, a square one
+/// fills the whole payload.
+pub const MAX_BITMAP_SIZE: u8 = 24;
+/// Furthest a vector primitive may reach from the aim point in pixels.
+const MAX_CUSTOM_OFFSET: i8 = 48;
+
+/// Vector primitive opcodes, each followed by its arguments as signed
+/// offsets from the aim point and, for circles, an unsigned diameter.
+const LINE: u8 = 1;
+const CIRCLE: u8 = 2;
+const DISC: u8 = 3;
+
+/// How a custom reticle is encoded.
+#[derive(PartialEq, Clone, Copy)]
+pub enum CustomFormat {
+    /// Width and height, then the rows from the top, each padded to whole
+    /// bytes with the leftmost pixel in the highest bit. The bitmap is
+    /// centred on the aim point.
+    Bitmap = 1,
+    /// A list of primitives: `1 x1 y1 x2 y2` for a line, `2 x y diameter`
+    /// for a circle and `3 x y diameter` for a filled circle.
+    Vector = 2,
+}
+
+/// A reticle designed off the sight and uploaded over the serial port. It
+/// only holds payloads that passed `CustomReticle::new`.
+#[derive(PartialEq, Clone, Copy)]
+pub struct CustomReticle {
+    format: Option<CustomFormat>,
+    len: u8,
+    payload: [u8; CUSTOM_PAYLOAD_BYTES],
+}
+
+impl CustomReticle {
+    /// Nothing uploaded yet.
+    pub const EMPTY: CustomReticle = CustomReticle {
+        format: None,
+        len: 0,
+        payload: [0; CUSTOM_PAYLOAD_BYTES],
+    };
+
+    /// Checks an uploaded payload, `None` if it does not describe a
+    /// reticle that fits the display.
+    pub fn new(format: CustomFormat, payload: &[u8]) -> Option<Self> {
+        if payload.len() > CUSTOM_PAYLOAD_BYTES {
+            return None;
+        }
+        let valid = match format {
+            CustomFormat::Bitmap => match payload {
+                [width, height, rows @ ..] => {
+                    (1..=MAX_BITMAP_SIZE).contains(width)
+                        && (1..=MAX_BITMAP_SIZE).contains(height)
+                        && rows.len() == width.div_ceil(8) as usize * *height as usize
+                }
+                _ => false,
+            },
+            CustomFormat::Vector => !payload.is_empty() && primitives_are_valid(payload),
+        };
+        if !valid {
+            return None;
+        }
+        let mut reticle = CustomReticle {
+            format: Some(format),
+            len: payload.len() as u8,
+            ..CustomReticle::EMPTY
+        };
+        reticle.payload[..payload.len()].copy_from_slice(payload);
+        Some(reticle)
+    }
+
+    fn payload(&self) -> &[u8] {
+        &self.payload[..self.len as usize]
+    }
+
+    /// Draws the reticle with its aim point at `center`, vector lines
+    /// `thickness` pixels wide. Without an upload only a dot marks the aim
+    /// point.
+    fn draw<T>(&self, target: &mut T, center: Point, color: Rgb565, thickness: u8)
+    where
+        T: DrawTarget<Color = Rgb565, Error: Debug>,
+    {
+        match self.format {
+            None => {
+                Circle::with_center(center, 3)
+                    .into_styled(PrimitiveStyle::with_fill(color))
+                    .draw(target)
+                    .unwrap();
+            }
+            Some(CustomFormat::Bitmap) => {
+                let payload = self.payload();
+                let (width, height) = (payload[0] as i32, payload[1] as i32);
+                let row_bytes = (width + 7) / 8;
+                let top_left = center - Point::new(width / 2, height / 2);
+                let pixels = (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .filter(|&(x, y)| {
+                        let byte = payload[2 + (y * row_bytes + x / 8) as usize];
+                        byte & (0x80 >> (x % 8)) != 0
+                    })
+                    .map(|(x, y)| Pixel(top_left + Point::new(x, y), color));
+                target.draw_iter(pixels).unwrap();
+            }
+            Some(CustomFormat::Vector) => {
+                let point = |x: u8, y: u8| center + Point::new(x as i8 as i32, y as i8 as i32);
+                let mut rest = self.payload();
+                while let [opcode, arguments @ ..] = rest {
+                    match (*opcode, arguments) {
+                        (LINE, [x1, y1, x2, y2, next @ ..]) => {
+                            Line::new(point(*x1, *y1), point(*x2, *y2))
+                                .into_styled(PrimitiveStyle::with_stroke(color, thickness as u32))
+                                .draw(target)
+                                .unwrap();
+                            rest = next;
+                        }
+                        (CIRCLE | DISC, [x, y, diameter, next @ ..]) => {
+                            let style = if *opcode == DISC {
+                                PrimitiveStyle::with_fill(color)
+                            } else {
+                                PrimitiveStyle::with_stroke(color, thickness as u32)
+                            };
+                            Circle::with_center(point(*x, *y), *diameter as u32)
+                                .into_styled(style)
+                                .draw(target)
+                                .unwrap();
+                            rest = next;
+                        }
+                        // Ruled out by `primitives_are_valid`.
+                        _ => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Format, length and payload as stored in the EEPROM. Borrowed, as the
+    /// reticle is kept out of `Sight` to avoid copying the bitmap.
+    pub fn as_record(&self) -> [u8; CUSTOM_RETICLE_BYTES] {
+        let mut bytes = [0; CUSTOM_RETICLE_BYTES];
+        bytes[0] = self.format.map_or(0, |format| format as u8);
+        bytes[1] = self.len;
+        bytes[2..].copy_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Reticle stored by `as_record`, checked as an upload would be.
+    pub fn from_bytes(bytes: &[u8; CUSTOM_RETICLE_BYTES]) -> Option<Self> {
+        let format = match bytes[0] {
+            0 => return Some(CustomReticle::EMPTY),
+            1 => CustomFormat::Bitmap,
+            2 => CustomFormat::Vector,
+            _ => return None,
+        };
+        let payload = bytes[2..].get(..bytes[1] as usize)?;
+        CustomReticle::new(format, payload)
+    }
+}
+
+/// Whether `payload` is a sequence of whole primitives that stay near the
+/// aim point.
+fn primitives_are_valid(mut payload: &[u8]) -> bool {
+    let offset_is_valid = |value: &u8| (*value as i8).unsigned_abs() <= MAX_CUSTOM_OFFSET as u8;
+    while let [opcode, arguments @ ..] = payload {
+        payload = match (*opcode, arguments) {
+            (LINE, [x1, y1, x2, y2, next @ ..]) => {
+                if ![x1, y1, x2, y2].into_iter().all(offset_is_valid) {
+                    return false;
+                }
+                next
+            }
+            (CIRCLE | DISC, [x, y, diameter, next @ ..]) => {
+                if ![x, y].into_iter().all(offset_is_valid)
+                    || !(1..=2 * MAX_CUSTOM_OFFSET as u8).contains(diameter)
+                {
+                    return false;
+                }
+                next
+            }
+            _ => return false,
+        };
+    }
+    true
+}
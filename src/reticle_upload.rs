@@ -0,0 +1,280 @@
+use crate::reticle::{CustomFormat, CustomReticle, CUSTOM_PAYLOAD_BYTES};
+
+/// Parses custom reticle uploads sent over the serial port.
+///
+/// An upload is one line framed like an NMEA sentence, `$RET,` followed by
+/// the format, `B` for a bitmap or `V` for vector primitives, a comma, the
+/// payload in hex and `*` with the XOR of every character between `$` and
+/// `*` as two hex digits, such as `$RET,B,0303A040A0*05` for a 3 by 3
+/// cross. See `CustomFormat` for the payload layouts. Lines that are not
+/// uploads are ignored, so the chronograph and the rangefinder can share
+/// the port. The sight answers each upload with `RET OK` or `RET ERR` and
+/// the reason.
+pub struct ReticleUploadParser {
+    state: State,
+    format: CustomFormat,
+    payload: [u8; CUSTOM_PAYLOAD_BYTES],
+    len: u8,
+    /// First hex digit of a payload byte whose second digit has not
+    /// arrived yet.
+    high_nibble: Option<u8>,
+    /// XOR of the characters so far.
+    checksum: u8,
+    received_checksum: u8,
+}
+
+/// Why an upload was rejected.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum UploadError {
+    /// The line does not follow the framing.
+    Malformed,
+    /// The line was corrupted on the way.
+    Checksum,
+    /// The payload does not describe a reticle that fits the display.
+    Invalid,
+}
+
+impl UploadError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            UploadError::Malformed => "malformed",
+            UploadError::Checksum => "checksum",
+            UploadError::Invalid => "invalid reticle",
+        }
+    }
+}
+
+const HEADER: &[u8] = b"RET,";
+/// Longest upload line, `$RET,` and the format, the payload in hex, the
+/// checksum and CR LF.
+pub const MAX_LINE_BYTES: usize = 1 + HEADER.len() + 2 + 2 * CUSTOM_PAYLOAD_BYTES + 3 + 2;
+
+#[derive(PartialEq, Clone, Copy)]
+enum State {
+    /// Start of a line, nothing read yet.
+    Start,
+    /// After the `$`, matching `HEADER`.
+    Header {
+        matched: u8,
+    },
+    Format,
+    /// After the format, expecting the comma.
+    FormatEnd,
+    Payload,
+    Checksum {
+        digits: u8,
+    },
+    /// After the checksum, waiting for the end of the line.
+    End,
+    /// Skipping a line that is not an upload.
+    Ignored,
+    /// Skipping the rest of a payload that does not fit.
+    TooLarge,
+    /// Skipping until the end of a malformed upload.
+    Invalid,
+}
+
+impl ReticleUploadParser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Start,
+            format: CustomFormat::Bitmap,
+            payload: [0; CUSTOM_PAYLOAD_BYTES],
+            len: 0,
+            high_nibble: None,
+            checksum: 0,
+            received_checksum: 0,
+        }
+    }
+
+    /// Feeds one received byte, returns the reticle or the reason it was
+    /// rejected once an upload line has ended.
+    pub fn push(&mut self, byte: u8) -> Option<Result<CustomReticle, UploadError>> {
+        if byte == b'\r' || byte == b'\n' {
+            let upload = self.finish();
+            self.reset();
+            return upload;
+        }
+        if matches!(
+            self.state,
+            State::Header { .. } | State::Format | State::FormatEnd | State::Payload
+        ) && byte != b'*'
+        {
+            self.checksum ^= byte;
+        }
+        self.state = match (self.state, byte) {
+            (State::Start, b'$') => State::Header { matched: 0 },
+            (State::Header { matched }, _) if HEADER[matched as usize] == byte => {
+                match matched as usize + 1 {
+                    matched if matched == HEADER.len() => State::Format,
+                    matched => State::Header {
+                        matched: matched as u8,
+                    },
+                }
+            }
+            (State::Format, b'B') => {
+                self.format = CustomFormat::Bitmap;
+                State::FormatEnd
+            }
+            (State::Format, b'V') => {
+                self.format = CustomFormat::Vector;
+                State::FormatEnd
+            }
+            (State::FormatEnd, b',') => State::Payload,
+            (State::Payload, b'*') if self.high_nibble.is_none() => State::Checksum { digits: 0 },
+            (State::Payload, _) => match (hex_digit(byte), self.high_nibble) {
+                (Some(_), Some(_)) if self.len as usize == CUSTOM_PAYLOAD_BYTES => State::TooLarge,
+                (Some(low), Some(high)) => {
+                    self.payload[self.len as usize] = high << 4 | low;
+                    self.len += 1;
+                    self.high_nibble = None;
+                    State::Payload
+                }
+                (Some(high), None) => {
+                    self.high_nibble = Some(high);
+                    State::Payload
+                }
+                _ => State::Invalid,
+            },
+            (State::Checksum { digits }, _) => match hex_digit(byte) {
+                Some(digit) => {
+                    self.received_checksum = self.received_checksum << 4 | digit;
+                    match digits + 1 {
+                        2 => State::End,
+                        digits => State::Checksum { digits },
+                    }
+                }
+                None => State::Invalid,
+            },
+            (State::Start | State::Header { .. } | State::Ignored, _) => State::Ignored,
+            (State::TooLarge, _) => State::TooLarge,
+            _ => State::Invalid,
+        };
+        None
+    }
+
+    fn finish(&self) -> Option<Result<CustomReticle, UploadError>> {
+        match self.state {
+            State::Start | State::Header { .. } | State::Ignored => None,
+            State::TooLarge => Some(Err(UploadError::Invalid)),
+            State::End if self.received_checksum != self.checksum => {
+                Some(Err(UploadError::Checksum))
+            }
+            State::End => Some(
+                CustomReticle::new(self.format, &self.payload[..self.len as usize])
+                    .ok_or(UploadError::Invalid),
+            ),
+            _ => Some(Err(UploadError::Malformed)),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `body` framed as an upload line with its checksum.
+    fn frame(body: &str) -> String {
+        let checksum = body.bytes().fold(0, |checksum, byte| checksum ^ byte);
+        format!("${body}*{checksum:02X}\r\n")
+    }
+
+    fn parse(line: &str) -> Option<Result<CustomReticle, UploadError>> {
+        let mut parser = ReticleUploadParser::new();
+        line.bytes().find_map(|byte| parser.push(byte))
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+    }
+
+    #[test]
+    fn reads_the_documented_example() {
+        let cross = CustomReticle::new(CustomFormat::Bitmap, &[3, 3, 0xA0, 0x40, 0xA0]);
+        assert!(parse("$RET,B,0303A040A0*05\r\n") == cross.map(Ok));
+        assert!(parse(&frame("RET,B,0303a040a0")) == cross.map(Ok));
+    }
+
+    #[test]
+    fn reads_vector_uploads() {
+        let payload = [1, 0xF6, 0, 10, 0, 2, 0, 0, 12];
+        let expected = CustomReticle::new(CustomFormat::Vector, &payload);
+        assert!(expected.is_some());
+        assert!(parse(&frame(&format!("RET,V,{}", hex(&payload)))) == expected.map(Ok));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        for line in [
+            "95.3 m/s\r\n",
+            "D=12.3m\r\n",
+            "$GPGGA,1*00\r\n",
+            "\r\n",
+            "$\r\n",
+        ] {
+            assert!(parse(line).is_none(), "{line}");
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_lines() {
+        assert!(parse("$RET,B,0303A040A0*06\r\n") == Some(Err(UploadError::Checksum)));
+        assert!(parse("$RET,B,0303A041A0*05\r\n") == Some(Err(UploadError::Checksum)));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            frame("RET,X,0303A040A0"),
+            frame("RET,B0303A040A0"),
+            frame("RET,B,0303A040G0"),
+            "$RET,B,0303A040A0\r\n".into(),
+            "$RET,B,0303A040A0*0\r\n".into(),
+            "$RET,B,0303A040A0*0G\r\n".into(),
+            "$RET,B,0303A040A0*05X\r\n".into(),
+        ] {
+            assert!(parse(&line) == Some(Err(UploadError::Malformed)), "{line}");
+        }
+    }
+
+    #[test]
+    fn rejects_an_odd_number_of_hex_digits() {
+        assert!(parse(&frame("RET,B,0303A040A")) == Some(Err(UploadError::Malformed)));
+    }
+
+    #[test]
+    fn rejects_oversized_payloads() {
+        let mut payload = [0xFF; CUSTOM_PAYLOAD_BYTES + 1];
+        payload[..2].copy_from_slice(&[24, 24]);
+        let line = frame(&format!("RET,B,{}", hex(&payload)));
+        assert!(parse(&line) == Some(Err(UploadError::Invalid)));
+    }
+
+    #[test]
+    fn rejects_reticles_that_do_not_fit() {
+        assert!(parse(&frame("RET,B,0003")) == Some(Err(UploadError::Invalid)));
+        assert!(parse(&frame("RET,V,01F600400A")) == Some(Err(UploadError::Invalid)));
+    }
+
+    #[test]
+    fn longest_upload_fills_a_line() {
+        let mut payload = [0xFF; CUSTOM_PAYLOAD_BYTES];
+        payload[..2].copy_from_slice(&[24, 24]);
+        let line = frame(&format!("RET,B,{}", hex(&payload)));
+        assert_eq!(line.len(), MAX_LINE_BYTES);
+        assert!(matches!(parse(&line), Some(Ok(_))));
+    }
+}
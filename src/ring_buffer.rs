@@ -0,0 +1,73 @@
+/// Bytes received by an interrupt and not read by the main loop yet. The
+/// hardware only holds two, so anything longer than a short line would be
+/// lost while the loop redraws the display.
+pub struct RingBuffer<const N: usize> {
+    bytes: [u8; N],
+    /// Index of the oldest byte.
+    start: u8,
+    len: u8,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    pub const fn new() -> Self {
+        assert!(N <= u8::MAX as usize);
+        Self {
+            bytes: [0; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends `byte`, dropping it and returning `false` when full.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.len as usize == N {
+            return false;
+        }
+        self.bytes[(self.start as usize + self.len as usize) % N] = byte;
+        self.len += 1;
+        true
+    }
+
+    /// Takes the oldest byte out.
+    pub fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.bytes[self.start as usize];
+        self.start = ((self.start as usize + 1) % N) as u8;
+        self.len -= 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_bytes_in_order_across_the_wrap() {
+        let mut buffer = RingBuffer::<4>::new();
+        for round in 0..5u8 {
+            for byte in 0..3 {
+                assert!(buffer.push(round * 3 + byte));
+            }
+            for byte in 0..3 {
+                assert_eq!(buffer.pop(), Some(round * 3 + byte));
+            }
+            assert_eq!(buffer.pop(), None);
+        }
+    }
+
+    #[test]
+    fn drops_bytes_when_full() {
+        let mut buffer = RingBuffer::<3>::new();
+        assert!(buffer.push(1) && buffer.push(2) && buffer.push(3));
+        assert!(!buffer.push(4));
+        assert_eq!(buffer.pop(), Some(1));
+        assert!(buffer.push(5));
+        assert_eq!(buffer.pop(), Some(2));
+        assert_eq!(buffer.pop(), Some(3));
+        assert_eq!(buffer.pop(), Some(5));
+        assert_eq!(buffer.pop(), None);
+    }
+}
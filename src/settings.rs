@@ -4,8 +4,28 @@ mod ui;
 
 use crate::{
     encoder::RotaryEncoder,
+    reticle::CustomReticle,
     settings::{
-        sub_menus::{about_page::{AboutPage, ABOUT_PAGE}, main_menu::{MainMenuState, MainMenuType, MAIN_MENU}, sight_menu::{SightMenu, SIGHT_MENU}},
+        rendering::BracketSpacing,
+        sub_menus::{
+            about_page::{AboutPage, ABOUT_PAGE},
+            ammo_menu::{AmmoMenu, AMMO_MENU},
+            calibration_screen::CalibrationScreen,
+            chrono_menu::{ChronoMenu, CHRONO_MENU},
+            display_menu::{DisplayMenu, DISPLAY_MENU},
+            drop_table_menu::{DropTableMenu, DROP_TABLE_MENU},
+            environment_menu::{EnvironmentMenu, ENVIRONMENT_MENU},
+            lead_menu::{LeadMenu, LEAD_MENU},
+            lens_menu::{LensMenu, LENS_MENU},
+            light_curve_menu::{LightCurveMenu, LIGHT_CURVE_MENU},
+            main_menu::{MainMenuState, MainMenuType, MAIN_MENU},
+            preferences_menu::{PreferencesMenu, PREFERENCES_MENU},
+            range_menu::{RangeMenu, RANGE_MENU},
+            reticle_menu::{ReticleMenu, RETICLE_MENU},
+            sight_menu::{SightMenu, SIGHT_MENU},
+            stadia_screen::StadiaScreen,
+            wind_menu::{WindMenu, WIND_MENU},
+        },
         ui::{settings_page::SettingsPageState, ClickResult, SubMenuPointer, SubMenuPointerImpl},
     },
     sight::Sight,
@@ -33,6 +53,19 @@ enum RotorInput {
 enum SettingsMenu {
     MainMenu,
     Sight,
+    Reticle,
+    Display,
+    LightCurve,
+    Lens,
+    Calibration,
+    Range,
+    Stadia,
+    Ammo,
+    Chrono,
+    DropTable,
+    Wind,
+    Environment,
+    Lead,
     Settings,
     About,
 }
@@ -41,6 +74,20 @@ enum SettingsMenu {
 struct SubMenuStates {
     main_menu: SubMenuPointerImpl<MainMenuType>,
     sight_settings: SubMenuPointerImpl<SightMenu>, // Placeholder for other submenus
+    reticle: SubMenuPointerImpl<ReticleMenu>,
+    display: SubMenuPointerImpl<DisplayMenu>,
+    light_curve: SubMenuPointerImpl<LightCurveMenu>,
+    lens: SubMenuPointerImpl<LensMenu>,
+    calibration: SubMenuPointerImpl<CalibrationScreen>,
+    range: SubMenuPointerImpl<RangeMenu>,
+    stadia: SubMenuPointerImpl<StadiaScreen>,
+    ammo: SubMenuPointerImpl<AmmoMenu>,
+    chrono: SubMenuPointerImpl<ChronoMenu>,
+    drop_table: SubMenuPointerImpl<DropTableMenu>,
+    wind: SubMenuPointerImpl<WindMenu>,
+    environment: SubMenuPointerImpl<EnvironmentMenu>,
+    lead: SubMenuPointerImpl<LeadMenu>,
+    preferences: SubMenuPointerImpl<PreferencesMenu>,
     about: SubMenuPointerImpl<AboutPage>, // Placeholder for the about submenu
 }
 
@@ -55,6 +102,62 @@ impl SubMenuStates {
                 submenu: &SIGHT_MENU,
                 state: SettingsPageState::new(),
             },
+            reticle: SubMenuPointerImpl {
+                submenu: &RETICLE_MENU,
+                state: SettingsPageState::new(),
+            },
+            display: SubMenuPointerImpl {
+                submenu: &DISPLAY_MENU,
+                state: SettingsPageState::new(),
+            },
+            light_curve: SubMenuPointerImpl {
+                submenu: &LIGHT_CURVE_MENU,
+                state: SettingsPageState::new(),
+            },
+            lens: SubMenuPointerImpl {
+                submenu: &LENS_MENU,
+                state: SettingsPageState::new(),
+            },
+            calibration: SubMenuPointerImpl {
+                submenu: &CalibrationScreen,
+                state: BracketSpacing::new(20),
+            },
+            range: SubMenuPointerImpl {
+                submenu: &RANGE_MENU,
+                state: SettingsPageState::new(),
+            },
+            stadia: SubMenuPointerImpl {
+                submenu: &StadiaScreen,
+                state: BracketSpacing::new(28),
+            },
+            ammo: SubMenuPointerImpl {
+                submenu: &AMMO_MENU,
+                state: SettingsPageState::new(),
+            },
+            chrono: SubMenuPointerImpl {
+                submenu: &CHRONO_MENU,
+                state: SettingsPageState::new(),
+            },
+            drop_table: SubMenuPointerImpl {
+                submenu: &DROP_TABLE_MENU,
+                state: SettingsPageState::new(),
+            },
+            wind: SubMenuPointerImpl {
+                submenu: &WIND_MENU,
+                state: SettingsPageState::new(),
+            },
+            environment: SubMenuPointerImpl {
+                submenu: &ENVIRONMENT_MENU,
+                state: SettingsPageState::new(),
+            },
+            lead: SubMenuPointerImpl {
+                submenu: &LEAD_MENU,
+                state: SettingsPageState::new(),
+            },
+            preferences: SubMenuPointerImpl {
+                submenu: &PREFERENCES_MENU,
+                state: SettingsPageState::new(),
+            },
             about: SubMenuPointerImpl {
                 submenu: &ABOUT_PAGE,
                 state: SettingsPageState::new(),
@@ -66,8 +169,21 @@ impl SubMenuStates {
         match menu {
             SettingsMenu::MainMenu => Some(&mut self.main_menu),
             SettingsMenu::Sight => Some(&mut self.sight_settings),
+            SettingsMenu::Reticle => Some(&mut self.reticle),
+            SettingsMenu::Display => Some(&mut self.display),
+            SettingsMenu::LightCurve => Some(&mut self.light_curve),
+            SettingsMenu::Lens => Some(&mut self.lens),
+            SettingsMenu::Calibration => Some(&mut self.calibration),
+            SettingsMenu::Range => Some(&mut self.range),
+            SettingsMenu::Stadia => Some(&mut self.stadia),
+            SettingsMenu::Ammo => Some(&mut self.ammo),
+            SettingsMenu::Chrono => Some(&mut self.chrono),
+            SettingsMenu::DropTable => Some(&mut self.drop_table),
+            SettingsMenu::Wind => Some(&mut self.wind),
+            SettingsMenu::Environment => Some(&mut self.environment),
+            SettingsMenu::Lead => Some(&mut self.lead),
+            SettingsMenu::Settings => Some(&mut self.preferences),
             SettingsMenu::About => Some(&mut self .about),
-            _ => None,
         }
     }
 
@@ -75,8 +191,21 @@ impl SubMenuStates {
         match menu {
             SettingsMenu::MainMenu => Some(&self.main_menu),
             SettingsMenu::Sight => Some(&self.sight_settings),
+            SettingsMenu::Reticle => Some(&self.reticle),
+            SettingsMenu::Display => Some(&self.display),
+            SettingsMenu::LightCurve => Some(&self.light_curve),
+            SettingsMenu::Lens => Some(&self.lens),
+            SettingsMenu::Calibration => Some(&self.calibration),
+            SettingsMenu::Range => Some(&self.range),
+            SettingsMenu::Stadia => Some(&self.stadia),
+            SettingsMenu::Ammo => Some(&self.ammo),
+            SettingsMenu::Chrono => Some(&self.chrono),
+            SettingsMenu::DropTable => Some(&self.drop_table),
+            SettingsMenu::Wind => Some(&self.wind),
+            SettingsMenu::Environment => Some(&self.environment),
+            SettingsMenu::Lead => Some(&self.lead),
+            SettingsMenu::Settings => Some(&self.preferences),
             SettingsMenu::About => Some(&self .about),
-            _ => None,
         }
     }
 }
@@ -94,17 +223,20 @@ impl SettingsState {
         self.current_menu.is_some()
     }
 
+    /// Handles the encoder, `clicked` when its button was clicked since the
+    /// last update.
     pub fn update<A, B, SW>(
         &mut self,
         sight: &mut Sight,
         encoder: &mut RotaryEncoder<A, B, SW>,
+        clicked: bool,
     ) -> bool
     where
         A: InputPin,
         B: InputPin,
         SW: InputPin,
     {
-        if encoder.is_pressed().is_ok_and(|pressed| pressed) {
+        if clicked {
             self.rotor_position = encoder.position();
             self.handle_press(sight)
         } else {
@@ -119,12 +251,15 @@ impl SettingsState {
         }
     }
 
-    pub fn draw<DI>(&self, display: &mut DI, sight: &Sight)
+    pub fn draw<DI>(&self, display: &mut DI, sight: &Sight, custom_reticle: &CustomReticle)
     where
         DI: DrawTarget<Color = Rgb565, Error: Debug>,
     {
         if let Some(menu) = &self.current_menu {
-            let mut renderer = rendering::DefaultSettingsRenderer { display };
+            let mut renderer = rendering::DefaultSettingsRenderer {
+                display,
+                custom_reticle,
+            };
             let sub_menu = self.states.get_menu_const(*menu).unwrap_or_else(|| {
                 panic!("No submenu found for {:?}", *menu);
             });
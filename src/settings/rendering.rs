@@ -2,12 +2,15 @@ use core::fmt::Debug;
 use embedded_graphics::{
     mono_font::{ascii::FONT_6X10, MonoTextStyle},
     pixelcolor::Rgb565,
-    prelude::{DrawTarget, Point, RgbColor},
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{Line, PrimitiveStyle},
     text::{renderer::CharacterStyle, Text},
 };
 use embedded_graphics_core::Drawable;
 
-use crate::draw_reticle;
+use crate::{
+    ballistics::stadia::bracket_rows, draw_reticle, reticle::CustomReticle, settings::RotorInput,
+};
 
 pub enum TextType {
     Normal,
@@ -19,6 +22,37 @@ pub trait SettingsRenderer {
     fn render_text(&mut self, text: &str, row: u8, text_type: TextType);
     fn render_aditional_text(&mut self, text: &str, row: u8, text_type: TextType, length: i32);
     fn render_sight_preview(&mut self, sight: &crate::sight::Sight);
+    /// Draws a pair of ranging brackets `spacing` pixels apart, centred on
+    /// the display.
+    fn render_brackets(&mut self, spacing: i16);
+}
+
+/// Smallest and largest spacing of the ranging brackets in pixels.
+const MIN_BRACKET_SPACING: i16 = 2;
+const MAX_BRACKET_SPACING: i16 = 90;
+
+/// State of a full screen page that sizes something with the ranging
+/// brackets, the encoder opens or closes them a pixel at a time.
+pub struct BracketSpacing {
+    pub spacing_px: i16,
+}
+
+impl BracketSpacing {
+    pub(crate) fn new(spacing_px: i16) -> Self {
+        Self { spacing_px }
+    }
+
+    pub fn handle_input(&mut self, input: RotorInput) {
+        self.spacing_px = match input {
+            RotorInput::Up => self.spacing_px - 1,
+            RotorInput::Down => self.spacing_px + 1,
+        }
+        .clamp(MIN_BRACKET_SPACING, MAX_BRACKET_SPACING);
+    }
+
+    pub fn draw(&self, display: &mut dyn SettingsRenderer) {
+        display.render_brackets(self.spacing_px);
+    }
 }
 
 pub(crate) struct DefaultSettingsRenderer<'a, TGraphicsInterface>
@@ -26,6 +60,7 @@ where
     TGraphicsInterface: DrawTarget<Color = Rgb565, Error: Debug>,
 {
     pub display: &'a mut TGraphicsInterface,
+    pub custom_reticle: &'a CustomReticle,
 }
 
 impl<'a, TGraphicsInterface> SettingsRenderer for DefaultSettingsRenderer<'a, TGraphicsInterface>
@@ -53,7 +88,31 @@ where
     }
 
     fn render_sight_preview(&mut self, sight: &crate::sight::Sight) {
-        draw_reticle(self.display, sight);
+        draw_reticle(self.display, sight, self.custom_reticle);
+    }
+
+    fn render_brackets(&mut self, spacing: i16) {
+        let size = self.display.bounding_box().size;
+        let center = Point::new(size.width as i32 / 2, size.height as i32 / 2);
+        let style = PrimitiveStyle::with_stroke(Rgb565::GREEN, 1);
+        let half_width = 10;
+        let tick = 3;
+        for (direction, y) in [-1, 1].into_iter().zip(bracket_rows(center.y, spacing)) {
+            Line::new(
+                Point::new(center.x - half_width, y),
+                Point::new(center.x + half_width, y),
+            )
+            .into_styled(style)
+            .draw(self.display)
+            .unwrap();
+            // Ticks at both ends point towards the other bracket.
+            for x in [center.x - half_width, center.x + half_width] {
+                Line::new(Point::new(x, y), Point::new(x, y - direction * tick))
+                    .into_styled(style)
+                    .draw(self.display)
+                    .unwrap();
+            }
+        }
     }
 }
 
@@ -1,3 +1,17 @@
+pub mod ammo_menu;
+pub mod calibration_screen;
+pub mod chrono_menu;
+pub mod display_menu;
+pub mod drop_table_menu;
+pub mod environment_menu;
+pub mod lead_menu;
+pub mod lens_menu;
+pub mod light_curve_menu;
 pub mod main_menu;
+pub mod preferences_menu;
+pub mod range_menu;
+pub mod reticle_menu;
 pub mod sight_menu;
+pub mod stadia_screen;
+pub mod wind_menu;
 pub mod about_page;
\ No newline at end of file
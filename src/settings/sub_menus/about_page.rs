@@ -1,6 +1,12 @@
-use crate::settings::ui::{
-    settings_page::{NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl, TextLine},
-    Menu,
+use crate::{
+    settings::ui::{
+        settings_page::{
+            NavigationButton, Readout, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+            TextLine,
+        },
+        Menu,
+    },
+    units::Quantity,
 };
 use const_format::formatcp;
 
@@ -8,6 +14,7 @@ pub struct AboutPage {
     name: TextLine,
     version: TextLine,
     author: TextLine,
+    velocity: Readout,
     exit: NavigationButton,
 }
 
@@ -17,9 +24,9 @@ impl SettingsPage for AboutPage {
             Some(&self.name),
             Some(&self.version),
             Some(&self.author),
+            Some(&self.velocity),
             Some(&self.exit),
             None,
-            None,
         ]
     }
 }
@@ -31,5 +38,10 @@ pub const ABOUT_PAGE: AboutPage = AboutPage {
         text: formatcp!("Firmware Version: {}", env!("CARGO_PKG_VERSION")),
     },
     author: TextLine { text: "Author: Adam Grabski" },
+    velocity: Readout {
+        label: "Muzzle Vel",
+        quantity: Quantity::Velocity,
+        value: |sight| sight.ammo().muzzle_velocity.round() as i16,
+    },
     exit: NavigationButton { label: "Exit", action: || SettingsPageClickResult::Exit },
 };
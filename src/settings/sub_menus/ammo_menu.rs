@@ -0,0 +1,89 @@
+use crate::{
+    ballistics::ammo::{
+        AMMO_NAMES, BUILTIN_AMMO, MAX_USER_DIAMETER, MAX_USER_MASS_CG, MIN_USER_DIAMETER,
+        MIN_USER_MASS_CG,
+    },
+    fixed::Fixed,
+    settings::ui::settings_page::{
+        Choice, LockableSlider, NavigationButton, SettingsPage, SettingsPageClickResult,
+        SettingsPageControl, Slider,
+    },
+    sight::Sight,
+    units::Quantity,
+};
+
+pub struct AmmoMenu {
+    profile: Choice,
+    mass_slider: LockableSlider,
+    diameter_slider: LockableSlider,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for AmmoMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.profile),
+            Some(&self.mass_slider),
+            Some(&self.diameter_slider),
+            Some(&self.back_button),
+            None,
+            None,
+        ]
+    }
+}
+
+// Mass and diameter can only be changed for user-defined profiles, built-in
+// ones show theirs read-only. Velocity is set per profile on the Chrono page.
+// The user profiles keep their "User 1" and "User 2" names, there is no way to
+// type one with the encoder.
+pub const AMMO_MENU: AmmoMenu = AmmoMenu {
+    profile: Choice {
+        label: "Ammo",
+        options: &AMMO_NAMES,
+        on_change: |index, sight| {
+            sight.ammo_index = index as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.ammo_slot(),
+    },
+    mass_slider: LockableSlider {
+        slider: Slider {
+            label: "Mass cg",
+            quantity: Quantity::Plain,
+            min: MIN_USER_MASS_CG as i16,
+            max: MAX_USER_MASS_CG as i16,
+            on_change: |value, sight| {
+                if let Some(ammo) = sight.user_ammo_mut() {
+                    ammo.mass_g = Fixed::from_ratio(value as i32, 100);
+                }
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| (sight.ammo().mass_g * 100).round() as i16,
+        },
+        editable: is_user_ammo,
+    },
+    diameter_slider: LockableSlider {
+        slider: Slider {
+            label: "Dia 0.01mm",
+            quantity: Quantity::Plain,
+            min: MIN_USER_DIAMETER as i16,
+            max: MAX_USER_DIAMETER as i16,
+            on_change: |value, sight| {
+                if let Some(ammo) = sight.user_ammo_mut() {
+                    ammo.diameter_mm = Fixed::from_ratio(value as i32, 100);
+                }
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| (sight.ammo().diameter_mm * 100).round() as i16,
+        },
+        editable: is_user_ammo,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
+
+fn is_user_ammo(sight: &Sight) -> bool {
+    sight.ammo_slot() >= BUILTIN_AMMO.len()
+}
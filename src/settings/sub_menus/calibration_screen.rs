@@ -0,0 +1,52 @@
+use crate::{
+    ballistics::PixelScale,
+    fixed::Fixed,
+    settings::{
+        rendering::{BracketSpacing, SettingsRenderer, TextType},
+        ui::{ClickResult, Menu},
+        RotorInput, SettingsMenu,
+    },
+    sight::Sight,
+};
+
+/// Full screen brackets for calibrating the pixel scale. Looking through the
+/// lens, the encoder opens or closes the brackets until they sit on the
+/// marks of the calibration target set on the Lens page, a click stores the
+/// scale and returns to that page.
+pub struct CalibrationScreen;
+
+impl CalibrationScreen {
+    fn scale(&self, state: &BracketSpacing, sight: &Sight) -> PixelScale {
+        PixelScale::calibrated(sight.calibration_target, state.spacing_px)
+    }
+}
+
+impl Menu for CalibrationScreen {
+    type TState = BracketSpacing;
+
+    fn handle_input(&self, state: &mut BracketSpacing, _sight: &mut Sight, input: RotorInput) {
+        state.handle_input(input);
+    }
+
+    fn handle_click(
+        &self,
+        state: &mut BracketSpacing,
+        sight: &mut Sight,
+    ) -> ClickResult<SettingsMenu> {
+        sight.pixel_scale = self.scale(state, sight);
+        ClickResult::Navigate(SettingsMenu::Lens)
+    }
+
+    fn draw(&self, state: &BracketSpacing, display: &mut dyn SettingsRenderer, sight: &Sight) {
+        display.render_text("Frame the marks", 0, TextType::Normal);
+        let mut buffer = *b"     px/mil";
+        Fixed::from_ratio(self.scale(state, sight).pixels_per_mrad as i32, 100)
+            .format(2, &mut buffer[..5]);
+        display.render_text(
+            unsafe { str::from_utf8_unchecked(&buffer) },
+            8,
+            TextType::Normal,
+        );
+        state.draw(display);
+    }
+}
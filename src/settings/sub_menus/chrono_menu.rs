@@ -0,0 +1,64 @@
+use crate::{
+    settings::ui::settings_page::{
+        Choice, NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+        Slider, TextLine,
+    },
+    sight::MAX_CHRONO_VELOCITY,
+    units::Quantity,
+};
+
+pub struct ChronoMenu {
+    serial_hint: TextLine,
+    source: Choice,
+    velocity_slider: Slider,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for ChronoMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.serial_hint),
+            Some(&self.source),
+            Some(&self.velocity_slider),
+            Some(&self.back_button),
+            None,
+            None,
+        ]
+    }
+}
+
+// Everything here applies to the ammo profile selected on the Ammo page.
+// Readings received over serial land in the same place as manual entries.
+pub const CHRONO_MENU: ChronoMenu = ChronoMenu {
+    serial_hint: TextLine {
+        text: "Serial 57600 baud",
+    },
+    source: Choice {
+        label: "Velocity",
+        options: &["Nominal", "Chrono"],
+        on_change: |index, sight| {
+            let velocity = match index {
+                0 => 0,
+                _ => sight.ammo().muzzle_velocity.round() as u8,
+            };
+            sight.set_chrono_velocity(velocity);
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| (sight.chrono_velocity[sight.ammo_slot()] != 0) as usize,
+    },
+    velocity_slider: Slider {
+        label: "Vel",
+        quantity: Quantity::Velocity,
+        min: 30,
+        max: MAX_CHRONO_VELOCITY as i16,
+        on_change: |value, sight| {
+            sight.set_chrono_velocity(value as u8);
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.ammo().muzzle_velocity.round() as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -0,0 +1,84 @@
+use crate::{
+    appearance::{
+        BrightnessSource, MAX_BRIGHTNESS, MIN_BRIGHTNESS, RETICLE_COLORS, RETICLE_COLOR_NAMES,
+    },
+    settings::ui::settings_page::{
+        Choice, NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+        Slider,
+    },
+    settings::SettingsMenu,
+    units::Quantity,
+};
+
+pub struct DisplayMenu {
+    reticle_color: Choice,
+    brightness_slider: Slider,
+    brightness_source: Choice,
+    light_curve_button: NavigationButton,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for DisplayMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.reticle_color),
+            Some(&self.brightness_slider),
+            Some(&self.brightness_source),
+            Some(&self.light_curve_button),
+            Some(&self.back_button),
+            None,
+        ]
+    }
+
+    fn shows_preview(&self) -> bool {
+        true
+    }
+}
+
+// The manual brightness is kept while the sensor drives the panel, for
+// when it is switched back.
+pub const DISPLAY_MENU: DisplayMenu = DisplayMenu {
+    reticle_color: Choice {
+        label: "Colour",
+        options: &RETICLE_COLOR_NAMES,
+        on_change: |index, sight| {
+            sight.appearance.reticle_color = RETICLE_COLORS[index];
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.appearance.reticle_color as usize,
+    },
+    brightness_slider: Slider {
+        label: "Brightness",
+        quantity: Quantity::Plain,
+        min: MIN_BRIGHTNESS as i16,
+        max: MAX_BRIGHTNESS as i16,
+        on_change: |value, sight| {
+            sight.appearance.brightness = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.appearance.brightness as i16,
+    },
+    brightness_source: Choice {
+        label: "Auto",
+        options: &["Off", "Sensor"],
+        on_change: |index, sight| {
+            sight.appearance.brightness_source = match index {
+                0 => BrightnessSource::Manual,
+                _ => BrightnessSource::Sensor,
+            };
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.appearance.brightness_source {
+            BrightnessSource::Manual => 0,
+            BrightnessSource::Sensor => 1,
+        },
+    },
+    light_curve_button: NavigationButton {
+        label: "Light Curve",
+        action: || SettingsPageClickResult::Navigate(SettingsMenu::LightCurve),
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -0,0 +1,57 @@
+use crate::{
+    ballistics::MAX_IMPACT_CM,
+    settings::ui::settings_page::{
+        NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl, Slider,
+    },
+    units::Quantity,
+};
+
+pub struct DropTableMenu {
+    rows: [Slider; 5],
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for DropTableMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.rows[0]),
+            Some(&self.rows[1]),
+            Some(&self.rows[2]),
+            Some(&self.rows[3]),
+            Some(&self.rows[4]),
+            Some(&self.back_button),
+        ]
+    }
+}
+
+macro_rules! drop_row {
+    ($label:literal, $index:literal) => {
+        Slider {
+            label: $label,
+            quantity: Quantity::Centimetres,
+            min: -MAX_IMPACT_CM,
+            max: MAX_IMPACT_CM,
+            on_change: |value, sight| {
+                sight.drop_table.impacts_cm[$index] = value;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| sight.drop_table.impacts_cm[$index],
+        }
+    };
+}
+
+// Each row is how many centimetres below the point of aim the BB landed at
+// a metric range, shown in metric whatever the units.
+pub const DROP_TABLE_MENU: DropTableMenu = DropTableMenu {
+    rows: [
+        drop_row!("At 10m", 0),
+        drop_row!("At 20m", 1),
+        drop_row!("At 30m", 2),
+        drop_row!("At 40m", 3),
+        drop_row!("At 50m", 4),
+    ],
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -0,0 +1,107 @@
+use crate::{
+    ballistics::atmosphere::{AtmosphereSource, MAX_PRESSURE_HPA, MIN_PRESSURE_HPA},
+    settings::ui::settings_page::{
+        Choice, LockableSlider, NavigationButton, Readout, SettingsPage, SettingsPageClickResult,
+        SettingsPageControl, Slider,
+    },
+    units::Quantity,
+};
+
+pub struct EnvironmentMenu {
+    source: Choice,
+    temperature_slider: LockableSlider,
+    pressure_slider: LockableSlider,
+    altitude_slider: LockableSlider,
+    density: Readout,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for EnvironmentMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.source),
+            Some(&self.temperature_slider),
+            Some(&self.pressure_slider),
+            Some(&self.altitude_slider),
+            Some(&self.density),
+            Some(&self.back_button),
+        ]
+    }
+}
+
+/// Whether the air is entered by hand rather than read from the sensor.
+fn manual(sight: &crate::sight::Sight) -> bool {
+    sight.atmosphere.source == AtmosphereSource::Manual
+}
+
+// With the sensor selected temperature and pressure are its readings and
+// the altitude is ignored, so they are only shown. Manual pressure is the
+// sea level value from a weather report.
+pub const ENVIRONMENT_MENU: EnvironmentMenu = EnvironmentMenu {
+    source: Choice {
+        label: "Source",
+        options: &["Manual", "Sensor"],
+        on_change: |index, sight| {
+            sight.atmosphere.set_source(match index {
+                0 => AtmosphereSource::Manual,
+                _ => AtmosphereSource::Sensor,
+            });
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.atmosphere.source {
+            AtmosphereSource::Manual => 0,
+            AtmosphereSource::Sensor => 1,
+        },
+    },
+    temperature_slider: LockableSlider {
+        slider: Slider {
+            label: "Temp C",
+            quantity: Quantity::Plain,
+            min: -20,
+            max: 50,
+            on_change: |value, sight| {
+                sight.atmosphere.temperature_c = value as i8;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| sight.atmosphere.temperature_c as i16,
+        },
+        editable: manual,
+    },
+    pressure_slider: LockableSlider {
+        slider: Slider {
+            label: "Press hPa",
+            quantity: Quantity::Plain,
+            min: MIN_PRESSURE_HPA as i16,
+            max: MAX_PRESSURE_HPA as i16,
+            on_change: |value, sight| {
+                sight.atmosphere.pressure_hpa = value as u16;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| sight.atmosphere.pressure_hpa as i16,
+        },
+        editable: manual,
+    },
+    altitude_slider: LockableSlider {
+        slider: Slider {
+            label: "Alt x10m",
+            quantity: Quantity::Plain,
+            min: 0,
+            max: 300,
+            on_change: |value, sight| {
+                sight.atmosphere.altitude_m = value as u16 * 10;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| (sight.atmosphere.altitude_m / 10) as i16,
+        },
+        editable: manual,
+    },
+    density: Readout {
+        label: "Air g/m3",
+        quantity: Quantity::Plain,
+        value: |sight| (sight.atmosphere.density() * 1000).round() as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
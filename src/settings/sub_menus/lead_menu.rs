@@ -0,0 +1,70 @@
+use crate::{
+    ballistics::lead::TargetDirection,
+    settings::ui::settings_page::{
+        Choice, NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+        Slider,
+    },
+    units::Quantity,
+};
+
+pub struct LeadMenu {
+    enabled: Choice,
+    speed_slider: Slider,
+    direction: Choice,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for LeadMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.enabled),
+            Some(&self.speed_slider),
+            Some(&self.direction),
+            Some(&self.back_button),
+            None,
+            None,
+        ]
+    }
+}
+
+pub const LEAD_MENU: LeadMenu = LeadMenu {
+    enabled: Choice {
+        label: "Lead",
+        options: &["Off", "On"],
+        on_change: |index, sight| {
+            sight.lead.enabled = index == 1;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.lead.enabled as usize,
+    },
+    speed_slider: Slider {
+        label: "Speed",
+        quantity: Quantity::KilometresPerHour,
+        min: 0,
+        max: 30,
+        on_change: |value, sight| {
+            sight.lead.speed_kmh = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.lead.speed_kmh as i16,
+    },
+    direction: Choice {
+        label: "Moving",
+        options: &["Left", "Right"],
+        on_change: |index, sight| {
+            sight.lead.direction = match index {
+                0 => TargetDirection::Left,
+                _ => TargetDirection::Right,
+            };
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.lead.direction {
+            TargetDirection::Left => 0,
+            TargetDirection::Right => 1,
+        },
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
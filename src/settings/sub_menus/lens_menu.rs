@@ -0,0 +1,75 @@
+use crate::{
+    ballistics::{MAX_PIXELS_PER_MRAD, MIN_PIXELS_PER_MRAD},
+    settings::ui::settings_page::{
+        NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl, Slider,
+    },
+    settings::SettingsMenu,
+    units::Quantity,
+};
+
+pub struct LensMenu {
+    spacing_slider: Slider,
+    distance_slider: Slider,
+    calibrate_button: NavigationButton,
+    scale_slider: Slider,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for LensMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.spacing_slider),
+            Some(&self.distance_slider),
+            Some(&self.calibrate_button),
+            Some(&self.scale_slider),
+            Some(&self.back_button),
+            None,
+        ]
+    }
+}
+
+// The marks describe the calibration target, the scale can also be tuned
+// by hand after calibrating.
+pub const LENS_MENU: LensMenu = LensMenu {
+    spacing_slider: Slider {
+        label: "Marks",
+        quantity: Quantity::Centimetres,
+        min: 1,
+        max: 100,
+        on_change: |value, sight| {
+            sight.calibration_target.spacing_cm = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.calibration_target.spacing_cm as i16,
+    },
+    distance_slider: Slider {
+        label: "Marks at",
+        quantity: Quantity::Range,
+        min: 5,
+        max: 100,
+        on_change: |value, sight| {
+            sight.calibration_target.distance_m = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.calibration_target.distance_m as i16,
+    },
+    calibrate_button: NavigationButton {
+        label: "Calibrate",
+        action: || SettingsPageClickResult::Navigate(SettingsMenu::Calibration),
+    },
+    scale_slider: Slider {
+        label: "Px/mil x100",
+        quantity: Quantity::Plain,
+        min: MIN_PIXELS_PER_MRAD as i16,
+        max: MAX_PIXELS_PER_MRAD as i16,
+        on_change: |value, sight| {
+            sight.pixel_scale.pixels_per_mrad = value as u16;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.pixel_scale.pixels_per_mrad as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -0,0 +1,92 @@
+use crate::{
+    appearance::{RETICLE_COLORS, RETICLE_COLOR_NAMES},
+    settings::ui::settings_page::{
+        Choice, NavigationButton, Readout, SettingsPage, SettingsPageClickResult,
+        SettingsPageControl, Slider,
+    },
+    settings::SettingsMenu,
+    units::Quantity,
+};
+
+pub struct LightCurveMenu {
+    dark_slider: Slider,
+    bright_slider: Slider,
+    dark_color: Choice,
+    light: Readout,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for LightCurveMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.dark_slider),
+            Some(&self.bright_slider),
+            Some(&self.dark_color),
+            Some(&self.light),
+            Some(&self.back_button),
+            None,
+        ]
+    }
+}
+
+/// Choices for the dark colour, keeping the reticle colour or one of
+/// `RETICLE_COLORS`.
+const DARK_COLOR_NAMES: [&str; RETICLE_COLORS.len() + 1] = {
+    let mut names = ["Keep"; RETICLE_COLORS.len() + 1];
+    let mut index = 0;
+    while index < RETICLE_COLOR_NAMES.len() {
+        names[index + 1] = RETICLE_COLOR_NAMES[index];
+        index += 1;
+    }
+    names
+};
+
+// Light levels are in percent of the sensor's range. The slider ranges keep
+// the dark end below the bright one.
+pub const LIGHT_CURVE_MENU: LightCurveMenu = LightCurveMenu {
+    dark_slider: Slider {
+        label: "Dark %",
+        quantity: Quantity::Plain,
+        min: 0,
+        max: 45,
+        on_change: |value, sight| {
+            sight.appearance.light_curve.dark = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.appearance.light_curve.dark as i16,
+    },
+    bright_slider: Slider {
+        label: "Bright %",
+        quantity: Quantity::Plain,
+        min: 50,
+        max: 100,
+        on_change: |value, sight| {
+            sight.appearance.light_curve.bright = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.appearance.light_curve.bright as i16,
+    },
+    dark_color: Choice {
+        label: "Dark col",
+        options: &DARK_COLOR_NAMES,
+        on_change: |index, sight| {
+            sight.appearance.dark_color = index.checked_sub(1).map(|index| RETICLE_COLORS[index]);
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| {
+            sight
+                .appearance
+                .dark_color
+                .map_or(0, |color| color as usize + 1)
+        },
+    },
+    light: Readout {
+        label: "Light %",
+        quantity: Quantity::Plain,
+        value: |sight| sight.ambient_light.unwrap_or(0) as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Navigate(SettingsMenu::Display),
+    },
+};
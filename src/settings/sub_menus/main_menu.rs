@@ -9,6 +9,46 @@ pub const MAIN_MENU: NavigationMenu = NavigationMenu {
             label: "Sight",
             action: || SettingsMenu::Sight,
         },
+        MenuOption {
+            label: "Reticle",
+            action: || SettingsMenu::Reticle,
+        },
+        MenuOption {
+            label: "Display",
+            action: || SettingsMenu::Display,
+        },
+        MenuOption {
+            label: "Lens",
+            action: || SettingsMenu::Lens,
+        },
+        MenuOption {
+            label: "Range",
+            action: || SettingsMenu::Range,
+        },
+        MenuOption {
+            label: "Ammo",
+            action: || SettingsMenu::Ammo,
+        },
+        MenuOption {
+            label: "Chrono",
+            action: || SettingsMenu::Chrono,
+        },
+        MenuOption {
+            label: "Drop Table",
+            action: || SettingsMenu::DropTable,
+        },
+        MenuOption {
+            label: "Wind",
+            action: || SettingsMenu::Wind,
+        },
+        MenuOption {
+            label: "Environment",
+            action: || SettingsMenu::Environment,
+        },
+        MenuOption {
+            label: "Lead",
+            action: || SettingsMenu::Lead,
+        },
         MenuOption {
             label: "Settings",
             action: || SettingsMenu::Settings,
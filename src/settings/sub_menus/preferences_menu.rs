@@ -0,0 +1,102 @@
+use crate::{
+    settings::ui::settings_page::{
+        Choice, NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+        Slider,
+    },
+    sight::{MAX_CANT_THRESHOLD, MIN_CANT_THRESHOLD},
+    units::{AngleUnit, Quantity, RangeUnit, VelocityUnit},
+};
+
+pub struct PreferencesMenu {
+    cant_threshold_slider: Slider,
+    range_unit: Choice,
+    angle_unit: Choice,
+    velocity_unit: Choice,
+    readouts: Choice,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for PreferencesMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.cant_threshold_slider),
+            Some(&self.range_unit),
+            Some(&self.angle_unit),
+            Some(&self.velocity_unit),
+            Some(&self.readouts),
+            Some(&self.back_button),
+        ]
+    }
+}
+
+pub const PREFERENCES_MENU: PreferencesMenu = PreferencesMenu {
+    cant_threshold_slider: Slider {
+        label: "Cant Limit",
+        quantity: Quantity::Plain,
+        min: MIN_CANT_THRESHOLD as i16,
+        max: MAX_CANT_THRESHOLD as i16,
+        on_change: |value, sight| {
+            sight.cant_threshold_deg = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.cant_threshold_deg as i16,
+    },
+    range_unit: Choice {
+        label: "Range",
+        options: &["m", "yd"],
+        on_change: |index, sight| {
+            sight.units.range = match index {
+                0 => RangeUnit::Metres,
+                _ => RangeUnit::Yards,
+            };
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.units.range {
+            RangeUnit::Metres => 0,
+            RangeUnit::Yards => 1,
+        },
+    },
+    angle_unit: Choice {
+        label: "Clicks",
+        options: &["0.1 mil", "1/4 moa"],
+        on_change: |index, sight| {
+            sight.units.angle = match index {
+                0 => AngleUnit::Mrad,
+                _ => AngleUnit::Moa,
+            };
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.units.angle {
+            AngleUnit::Mrad => 0,
+            AngleUnit::Moa => 1,
+        },
+    },
+    velocity_unit: Choice {
+        label: "Velocity",
+        options: &["m/s", "fps"],
+        on_change: |index, sight| {
+            sight.units.velocity = match index {
+                0 => VelocityUnit::MetresPerSecond,
+                _ => VelocityUnit::FeetPerSecond,
+            };
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| match sight.units.velocity {
+            VelocityUnit::MetresPerSecond => 0,
+            VelocityUnit::FeetPerSecond => 1,
+        },
+    },
+    readouts: Choice {
+        label: "HUD",
+        options: &["Basic", "+Time", "+Energy", "+Both"],
+        on_change: |index, sight| {
+            sight.set_readouts(index as u8);
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.readouts() as usize,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
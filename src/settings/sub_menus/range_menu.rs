@@ -0,0 +1,64 @@
+use crate::{
+    ballistics::stadia::STADIA_TARGET_NAMES,
+    settings::ui::settings_page::{
+        Choice, NavigationButton, Readout, SettingsPage, SettingsPageClickResult,
+        SettingsPageControl,
+    },
+    settings::SettingsMenu,
+    units::Quantity,
+};
+
+pub struct RangeMenu {
+    rangefinder: Choice,
+    measured_range: Readout,
+    stadia_target: Choice,
+    stadia_button: NavigationButton,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for RangeMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.rangefinder),
+            Some(&self.measured_range),
+            Some(&self.stadia_target),
+            Some(&self.stadia_button),
+            Some(&self.back_button),
+            None,
+        ]
+    }
+}
+
+pub const RANGE_MENU: RangeMenu = RangeMenu {
+    rangefinder: Choice {
+        label: "Rangefinder",
+        options: &["Off", "On"],
+        on_change: |index, sight| {
+            sight.rangefinder_enabled = index == 1;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.rangefinder_enabled as usize,
+    },
+    measured_range: Readout {
+        label: "Last reading",
+        quantity: Quantity::Range,
+        value: |sight| sight.measured_range.unwrap_or(0) as i16,
+    },
+    stadia_target: Choice {
+        label: "Frame",
+        options: &STADIA_TARGET_NAMES,
+        on_change: |index, sight| {
+            sight.stadia_target = index as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.stadia_target as usize,
+    },
+    stadia_button: NavigationButton {
+        label: "Bracket Ranging",
+        action: || SettingsPageClickResult::Navigate(SettingsMenu::Stadia),
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
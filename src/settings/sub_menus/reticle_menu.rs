@@ -0,0 +1,97 @@
+use crate::{
+    reticle::{MAX_SIZE, MAX_THICKNESS, MIN_SIZE, MIN_THICKNESS, RETICLE_KINDS, RETICLE_NAMES},
+    settings::ui::settings_page::{
+        Choice, NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl,
+        Slider,
+    },
+    sight::{MAX_LADDER_MAX, MAX_LADDER_STEP, MIN_LADDER_MAX, MIN_LADDER_STEP},
+    units::Quantity,
+};
+
+pub struct ReticleMenu {
+    kind: Choice,
+    size_slider: Slider,
+    thickness_slider: Slider,
+    ladder_step_slider: Slider,
+    ladder_max_slider: Slider,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for ReticleMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.kind),
+            Some(&self.size_slider),
+            Some(&self.thickness_slider),
+            Some(&self.ladder_step_slider),
+            Some(&self.ladder_max_slider),
+            Some(&self.back_button),
+        ]
+    }
+
+    fn shows_preview(&self) -> bool {
+        true
+    }
+}
+
+// Size and thickness apply to the pattern currently chosen, each pattern
+// remembers its own. The ladder ranges only matter for the BDC ladder.
+pub const RETICLE_MENU: ReticleMenu = ReticleMenu {
+    kind: Choice {
+        label: "Reticle",
+        options: &RETICLE_NAMES,
+        on_change: |index, sight| {
+            sight.reticle.kind = RETICLE_KINDS[index];
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.reticle.index(),
+    },
+    size_slider: Slider {
+        label: "Size px",
+        quantity: Quantity::Plain,
+        min: MIN_SIZE as i16,
+        max: MAX_SIZE as i16,
+        on_change: |value, sight| {
+            sight.reticle.selected_mut().size = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.reticle.selected().size as i16,
+    },
+    thickness_slider: Slider {
+        label: "Line px",
+        quantity: Quantity::Plain,
+        min: MIN_THICKNESS as i16,
+        max: MAX_THICKNESS as i16,
+        on_change: |value, sight| {
+            sight.reticle.selected_mut().thickness = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.reticle.selected().thickness as i16,
+    },
+    ladder_step_slider: Slider {
+        label: "Ladder step",
+        quantity: Quantity::Range,
+        min: MIN_LADDER_STEP as i16,
+        max: MAX_LADDER_STEP as i16,
+        on_change: |value, sight| {
+            sight.ladder.step = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.ladder.step as i16,
+    },
+    ladder_max_slider: Slider {
+        label: "Ladder to",
+        quantity: Quantity::Range,
+        min: MIN_LADDER_MAX as i16,
+        max: MAX_LADDER_MAX as i16,
+        on_change: |value, sight| {
+            sight.ladder.max = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.ladder.max as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -1,11 +1,20 @@
-use crate::settings::ui::settings_page::{
-    NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl, Slider
+use crate::{
+    ballistics::MAX_HOP_STRENGTH,
+    settings::ui::settings_page::{
+        Choice, ClickSlider, NavigationButton, SettingsPage, SettingsPageClickResult,
+        SettingsPageControl, Slider,
+    },
+    sight::{DropSource, MAX_ZERO_RANGE, MIN_ZERO_RANGE},
+    units::Quantity,
 };
 
 pub struct SightMenu {
     back_button: NavigationButton,
-    x_slider: Slider,
-    y_slider: Slider,
+    x_slider: ClickSlider,
+    y_slider: ClickSlider,
+    zero_range_slider: Slider,
+    hop_slider: Slider,
+    drop_source: Choice,
 }
 
 impl SettingsPage for SightMenu {
@@ -13,37 +22,92 @@ impl SettingsPage for SightMenu {
         [
             Some(&self.x_slider),
             Some(&self.y_slider),
+            Some(&self.zero_range_slider),
+            Some(&self.hop_slider),
+            Some(&self.drop_source),
             Some(&self.back_button),
-            None,
-            None,
-            None,
         ]
     }
 }
 
+/// Zero adjustment range either way, in hundredths of a milliradian.
+const MAX_ZERO_ADJUSTMENT: i16 = 3000;
+
+/// Zero clicks move the reticle by `PixelScale::min_adjustment`, so the
+/// readout steps by what the sight can show rather than by a finer unit
+/// step that would not move the reticle.
+fn zero_click(sight: &crate::sight::Sight) -> i16 {
+    sight.pixel_scale.min_adjustment()
+}
+
 pub const SIGHT_MENU: SightMenu = SightMenu {
     back_button: NavigationButton {
         label: "Back",
         action: || SettingsPageClickResult::Exit,
     },
-    x_slider: Slider {
-        label: "X Zero",
-        min: -50,
-        max: 50,
+    x_slider: ClickSlider {
+        slider: Slider {
+            label: "X Zero",
+            quantity: Quantity::Angle,
+            min: -MAX_ZERO_ADJUSTMENT,
+            max: MAX_ZERO_ADJUSTMENT,
+            on_change: |value, sight| {
+                sight.x_zero = value;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| sight.x_zero,
+        },
+        click: zero_click,
+    },
+    y_slider: ClickSlider {
+        slider: Slider {
+            label: "Y Zero",
+            quantity: Quantity::Angle,
+            min: -MAX_ZERO_ADJUSTMENT,
+            max: MAX_ZERO_ADJUSTMENT,
+            on_change: |value, sight| {
+                sight.y_zero = value;
+                SettingsPageClickResult::None
+            },
+            curr_value: |sight| sight.y_zero,
+        },
+        click: zero_click,
+    },
+    zero_range_slider: Slider {
+        label: "Zeroed at",
+        quantity: Quantity::Range,
+        min: MIN_ZERO_RANGE as i16,
+        max: MAX_ZERO_RANGE as i16,
         on_change: |value, sight| {
-            sight.x_zero = value;
+            sight.zero_range = value as u8;
             SettingsPageClickResult::None
         },
-        curr_value: |sight| sight.x_zero,
+        curr_value: |sight| sight.zero_range as i16,
     },
-    y_slider: Slider {
-        label: "Y Zero",
-        min: -50,
-        max: 50,
+    hop_slider: Slider {
+        label: "Hop",
+        quantity: Quantity::Plain,
+        min: 0,
+        max: MAX_HOP_STRENGTH as i16,
         on_change: |value, sight| {
-            sight.y_zero = value;
+            sight.hop_strength = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.hop_strength as i16,
+    },
+    drop_source: Choice {
+        label: "Drop",
+        options: &["Model", "Table"],
+        on_change: |index, sight| {
+            sight.drop_source = match index {
+                0 => DropSource::Model,
+                _ => DropSource::Measured,
+            };
             SettingsPageClickResult::None
         },
-        curr_value: |sight| sight.y_zero,
+        curr_value: |sight| match sight.drop_source {
+            DropSource::Model => 0,
+            DropSource::Measured => 1,
+        },
     },
 };
@@ -0,0 +1,59 @@
+use crate::{
+    ballistics::stadia::{self, STADIA_TARGETS},
+    settings::{
+        rendering::{BracketSpacing, SettingsRenderer, TextType},
+        ui::{ClickResult, Menu},
+        RotorInput, SettingsMenu,
+    },
+    sight::Sight,
+    units::Quantity,
+};
+
+/// Full screen brackets for stadiametric ranging. Turning the encoder opens
+/// or closes the brackets around the target chosen on the Range page, a
+/// click sets the range and returns to the sight.
+pub struct StadiaScreen;
+
+impl StadiaScreen {
+    fn range(&self, state: &BracketSpacing, sight: &Sight) -> u8 {
+        let target = &STADIA_TARGETS[sight.stadia_target as usize];
+        stadia::range(target.height_cm, state.spacing_px, sight.pixel_scale)
+    }
+}
+
+impl Menu for StadiaScreen {
+    type TState = BracketSpacing;
+
+    fn handle_input(&self, state: &mut BracketSpacing, _sight: &mut Sight, input: RotorInput) {
+        state.handle_input(input);
+    }
+
+    fn handle_click(
+        &self,
+        state: &mut BracketSpacing,
+        sight: &mut Sight,
+    ) -> ClickResult<SettingsMenu> {
+        sight.range = self.range(state, sight);
+        ClickResult::Back
+    }
+
+    fn draw(&self, state: &BracketSpacing, display: &mut dyn SettingsRenderer, sight: &Sight) {
+        display.render_text(
+            STADIA_TARGETS[sight.stadia_target as usize].name,
+            0,
+            TextType::Normal,
+        );
+        let mut buffer = *b"RNG:      ";
+        sight.units.format(
+            Quantity::Range,
+            self.range(state, sight) as i16,
+            &mut buffer[4..],
+        );
+        display.render_text(
+            unsafe { str::from_utf8_unchecked(&buffer) },
+            8,
+            TextType::Normal,
+        );
+        state.draw(display);
+    }
+}
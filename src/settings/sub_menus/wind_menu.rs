@@ -0,0 +1,54 @@
+use crate::{
+    settings::ui::settings_page::{
+        NavigationButton, SettingsPage, SettingsPageClickResult, SettingsPageControl, Slider,
+    },
+    units::Quantity,
+};
+
+pub struct WindMenu {
+    speed_slider: Slider,
+    clock_slider: Slider,
+    back_button: NavigationButton,
+}
+
+impl SettingsPage for WindMenu {
+    fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6] {
+        [
+            Some(&self.speed_slider),
+            Some(&self.clock_slider),
+            Some(&self.back_button),
+            None,
+            None,
+            None,
+        ]
+    }
+}
+
+pub const WIND_MENU: WindMenu = WindMenu {
+    speed_slider: Slider {
+        label: "Speed",
+        quantity: Quantity::Velocity,
+        min: 0,
+        max: 20,
+        on_change: |value, sight| {
+            sight.wind.speed = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.wind.speed as i16,
+    },
+    clock_slider: Slider {
+        label: "From o'clock",
+        quantity: Quantity::Plain,
+        min: 1,
+        max: 12,
+        on_change: |value, sight| {
+            sight.wind.clock = value as u8;
+            SettingsPageClickResult::None
+        },
+        curr_value: |sight| sight.wind.clock as i16,
+    },
+    back_button: NavigationButton {
+        label: "Back",
+        action: || SettingsPageClickResult::Exit,
+    },
+};
@@ -5,6 +5,9 @@ use crate::{
     sight::Sight,
 };
 
+/// Text rows that fit on the display.
+const VISIBLE_ROWS: usize = 9;
+
 pub struct MenuOption<TResult> {
     pub label: &'static str,
     pub action: fn() -> TResult,
@@ -48,13 +51,21 @@ impl Menu for NavigationMenu {
 
     fn draw(&self, state: &NavigationMenuState, display: &mut dyn SettingsRenderer, sight: &Sight)
     {
-        for (index, option) in self.options.iter().enumerate() {
+        // Scroll so the selected option stays on screen.
+        let first = (state.selected_index as usize).saturating_sub(VISIBLE_ROWS - 1);
+        for (index, option) in self
+            .options
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE_ROWS)
+        {
             let text_type = if index == state.selected_index as usize {
                 crate::settings::rendering::TextType::Highlighted
             } else {
                 crate::settings::rendering::TextType::Normal
             };
-            display.render_text(option.label, index as u8, text_type);
+            display.render_text(option.label, (index - first) as u8, text_type);
         }
 
     }
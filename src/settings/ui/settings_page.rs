@@ -1,12 +1,12 @@
 use embedded_graphics::prelude::Point;
 
 use crate::{
-    format_two_digit, format_two_digit_16,
     settings::{
         rendering::{SettingsRenderer, TextType},
         ui::{ClickResult, Menu},
         RotorInput, SettingsMenu,
     },
+    units::Quantity,
 };
 
 pub enum SettingsPageClickResult {
@@ -19,6 +19,12 @@ pub enum SettingsPageClickResult {
 
 pub trait SettingsPage {
     fn controls(&self) -> [Option<&dyn SettingsPageControl>; 6];
+
+    /// Whether the sight is drawn behind the controls the whole time, not
+    /// only while a slider is selected.
+    fn shows_preview(&self) -> bool {
+        false
+    }
 }
 
 pub struct NavigationButton {
@@ -28,12 +34,44 @@ pub struct NavigationButton {
 
 pub struct Slider {
     pub label: &'static str,
+    /// Limits and values are in the base unit of `quantity`, one step of
+    /// the encoder moves by one step of the unit the user picked.
+    pub quantity: Quantity,
     pub min: i16,
     pub max: i16,
     pub on_change: fn(new_value: i16, sight: &mut crate::sight::Sight) -> SettingsPageClickResult,
     pub curr_value: fn(sight: &crate::sight::Sight) -> i16,
 }
 
+/// Read-only value shown in the user's units.
+pub struct Readout {
+    pub label: &'static str,
+    pub quantity: Quantity,
+    pub value: fn(sight: &crate::sight::Sight) -> i16,
+}
+
+/// Slider that can only be moved while `editable` holds, and is shown as a
+/// `Readout` otherwise.
+pub struct LockableSlider {
+    pub slider: Slider,
+    pub editable: fn(sight: &crate::sight::Sight) -> bool,
+}
+
+/// Slider whose encoder detents each move by `click` base units, for values
+/// where a step of the user's unit can be finer than anything the sight
+/// shows.
+pub struct ClickSlider {
+    pub slider: Slider,
+    pub click: fn(sight: &crate::sight::Sight) -> i16,
+}
+
+pub struct Choice {
+    pub label: &'static str,
+    pub options: &'static [&'static str],
+    pub on_change: fn(new_index: usize, sight: &mut crate::sight::Sight) -> SettingsPageClickResult,
+    pub curr_value: fn(sight: &crate::sight::Sight) -> usize,
+}
+
 pub struct TextLine {
     pub text: &'static str,
 }
@@ -70,16 +108,16 @@ impl SettingsPageControl for Slider {
         match input {
             crate::settings::RotorInput::Up => {
                 if current_value < self.max {
-                    current_value += 1;
+                    current_value = sight.units.step_by(self.quantity, current_value, 1);
                 }
             }
             crate::settings::RotorInput::Down => {
                 if current_value > self.min {
-                    current_value -= 1;
+                    current_value = sight.units.step_by(self.quantity, current_value, -1);
                 }
             }
         }
-        (self.on_change)(current_value, sight);
+        (self.on_change)(current_value.clamp(self.min, self.max), sight);
     }
 
     fn handle_click(
@@ -115,18 +153,184 @@ impl SettingsPageControl for Slider {
         });
         if active {
             display.render_sight_preview(sight);
-            let mut buffer = *b"ABCD";
-            format_two_digit_16((self.curr_value)(sight), &mut buffer);
-            display.render_aditional_text(
-                unsafe { str::from_utf8_unchecked(&buffer) },
-                row,
-                TextType::Normal,
-                4,
-            );
+            render_quantity(display, sight, self.quantity, (self.curr_value)(sight), row);
+        }
+    }
+}
+
+impl SettingsPageControl for Readout {
+    fn handle_input(&self, _sight: &mut crate::sight::Sight, _input: crate::settings::RotorInput) {
+        // Readouts do not handle input
+    }
+
+    fn handle_click(
+        &self,
+        _has_focus: bool,
+        _sight: &mut crate::sight::Sight,
+    ) -> SettingsPageClickResult {
+        SettingsPageClickResult::None
+    }
+
+    fn draw(
+        &self,
+        display: &mut dyn SettingsRenderer,
+        sight: &crate::sight::Sight,
+        row: u8,
+        active: bool,
+        _focused: bool,
+    ) {
+        display.render_text(
+            self.label,
+            row,
+            if active {
+                TextType::Highlighted
+            } else {
+                TextType::Normal
+            },
+        );
+        render_quantity(display, sight, self.quantity, (self.value)(sight), row);
+    }
+}
+
+impl SettingsPageControl for LockableSlider {
+    fn handle_input(&self, sight: &mut crate::sight::Sight, input: crate::settings::RotorInput) {
+        if (self.editable)(sight) {
+            self.slider.handle_input(sight, input);
+        }
+    }
+
+    fn handle_click(
+        &self,
+        has_focus: bool,
+        sight: &mut crate::sight::Sight,
+    ) -> SettingsPageClickResult {
+        if has_focus || (self.editable)(sight) {
+            self.slider.handle_click(has_focus, sight)
+        } else {
+            SettingsPageClickResult::None
+        }
+    }
+
+    fn draw(
+        &self,
+        display: &mut dyn SettingsRenderer,
+        sight: &crate::sight::Sight,
+        row: u8,
+        active: bool,
+        focused: bool,
+    ) {
+        if (self.editable)(sight) {
+            self.slider.draw(display, sight, row, active, focused);
+        } else {
+            let readout = Readout {
+                label: self.slider.label,
+                quantity: self.slider.quantity,
+                value: self.slider.curr_value,
+            };
+            readout.draw(display, sight, row, active, focused);
         }
     }
 }
 
+impl SettingsPageControl for ClickSlider {
+    fn handle_input(&self, sight: &mut crate::sight::Sight, input: crate::settings::RotorInput) {
+        let click = (self.click)(sight);
+        let current_value = (self.slider.curr_value)(sight);
+        let new_value = match input {
+            crate::settings::RotorInput::Up => current_value.saturating_add(click),
+            crate::settings::RotorInput::Down => current_value.saturating_sub(click),
+        };
+        (self.slider.on_change)(new_value.clamp(self.slider.min, self.slider.max), sight);
+    }
+
+    fn handle_click(
+        &self,
+        has_focus: bool,
+        sight: &mut crate::sight::Sight,
+    ) -> SettingsPageClickResult {
+        self.slider.handle_click(has_focus, sight)
+    }
+
+    fn draw(
+        &self,
+        display: &mut dyn SettingsRenderer,
+        sight: &crate::sight::Sight,
+        row: u8,
+        active: bool,
+        focused: bool,
+    ) {
+        self.slider.draw(display, sight, row, active, focused);
+    }
+}
+
+/// Draws `value` with its unit at the right edge of `row`.
+fn render_quantity(
+    display: &mut dyn SettingsRenderer,
+    sight: &crate::sight::Sight,
+    quantity: Quantity,
+    value: i16,
+    row: u8,
+) {
+    let mut buffer = [b' '; 10];
+    sight.units.format(quantity, value, &mut buffer);
+    let start = buffer.iter().position(|byte| *byte != b' ').unwrap_or(0);
+    display.render_aditional_text(
+        unsafe { str::from_utf8_unchecked(&buffer[start..]) },
+        row,
+        TextType::Normal,
+        (buffer.len() - start) as i32,
+    );
+}
+
+impl SettingsPageControl for Choice {
+    fn handle_input(&self, sight: &mut crate::sight::Sight, input: crate::settings::RotorInput) {
+        let current_index = (self.curr_value)(sight);
+        // Cycle through the options, wrapping around at both ends
+        let new_index = match input {
+            crate::settings::RotorInput::Up => (current_index + 1) % self.options.len(),
+            crate::settings::RotorInput::Down => {
+                (current_index + self.options.len() - 1) % self.options.len()
+            }
+        };
+        (self.on_change)(new_index, sight);
+    }
+
+    fn handle_click(
+        &self,
+        has_focus: bool,
+        _sight: &mut crate::sight::Sight,
+    ) -> SettingsPageClickResult {
+        if has_focus {
+            SettingsPageClickResult::LoseFocus
+        } else {
+            SettingsPageClickResult::GainFocus
+        }
+    }
+
+    fn draw(
+        &self,
+        display: &mut dyn SettingsRenderer,
+        sight: &crate::sight::Sight,
+        row: u8,
+        active: bool,
+        focused: bool,
+    ) {
+        display.render_text(self.label, row, {
+            if active {
+                if focused {
+                    TextType::Selected
+                } else {
+                    TextType::Highlighted
+                }
+            } else {
+                TextType::Normal
+            }
+        });
+        let option = self.options[(self.curr_value)(sight)];
+        display.render_aditional_text(option, row, TextType::Normal, option.len() as i32);
+    }
+}
+
 impl SettingsPageControl for NavigationButton {
     fn handle_input(&self, _sight: &mut crate::sight::Sight, _input: crate::settings::RotorInput) {
         // Handle input for the navigation button
@@ -265,6 +469,9 @@ impl<T: SettingsPage> Menu for T {
         _display: &mut dyn crate::settings::rendering::SettingsRenderer,
         _sight: &crate::sight::Sight,
     ) {
+        if self.shows_preview() {
+            _display.render_sight_preview(_sight);
+        }
         let controls = self.controls();
         for (index, control) in controls.iter().enumerate() {
             let active = index == state.active_control;
@@ -1,7 +1,459 @@
+use crate::ballistics::{
+    ammo::{AmmoProfile, AMMO_NAMES, BUILTIN_AMMO, DEFAULT_USER_AMMO, USER_AMMO_SLOTS},
+    atmosphere::Atmosphere,
+    incline,
+    lead::Lead,
+    CalibrationTarget, DropTable, Holdover, HoldoverTable, PixelScale, Wind,
+};
+use crate::{
+    appearance::{
+        Appearance, BrightnessSource, ReticleColor, MIN_BRIGHTNESS, NIGHT_VISION_BRIGHTNESS,
+    },
+    fixed::Fixed,
+    reticle::{LadderMark, Reticle},
+    units::{Quantity, Units},
+};
+
+/// Where the vertical holdover comes from.
+#[derive(PartialEq, Clone, Copy)]
+pub enum DropSource {
+    /// Computed from the ammo profile and hop strength.
+    Model,
+    /// Interpolated from the measured `DropTable`.
+    Measured,
+}
+
+/// Limits of the distance between two BDC ladder marks in metres.
+pub const MIN_LADDER_STEP: u8 = 5;
+pub const MAX_LADDER_STEP: u8 = 20;
+/// Limits of the range of the farthest BDC ladder mark in metres.
+pub const MIN_LADDER_MAX: u8 = 10;
+pub const MAX_LADDER_MAX: u8 = 80;
+
+/// Ranges in metres the BDC ladder marks.
+#[derive(PartialEq, Clone, Copy)]
+pub struct LadderSpacing {
+    pub step: u8,
+    pub max: u8,
+}
+
+/// Bytes `LadderSpacing::to_bytes` produces.
+pub const LADDER_SPACING_BYTES: usize = 2;
+
+impl LadderSpacing {
+    pub const DEFAULT: LadderSpacing = LadderSpacing { step: 10, max: 60 };
+
+    /// The spacing as stored in the EEPROM.
+    pub fn to_bytes(self) -> [u8; LADDER_SPACING_BYTES] {
+        [self.step, self.max]
+    }
+
+    /// Spacing stored by `to_bytes`, if both ranges are within their limits.
+    pub fn from_bytes(bytes: &[u8; LADDER_SPACING_BYTES]) -> Option<Self> {
+        let [step, max] = *bytes;
+        ((MIN_LADDER_STEP..=MAX_LADDER_STEP).contains(&step)
+            && (MIN_LADDER_MAX..=MAX_LADDER_MAX).contains(&max))
+        .then_some(LadderSpacing { step, max })
+    }
+}
+
+/// Limits of the cant warning threshold in degrees.
+pub const MIN_CANT_THRESHOLD: u8 = 1;
+pub const MAX_CANT_THRESHOLD: u8 = 15;
+
+/// Limits of the zero range in metres.
+pub const MIN_ZERO_RANGE: u8 = 5;
+pub const MAX_ZERO_RANGE: u8 = 80;
+
+/// Fastest chronograph reading kept, in m/s. Anything faster is not a BB,
+/// and leaves the erased EEPROM value out of range.
+pub const MAX_CHRONO_VELOCITY: u8 = 250;
+
 #[derive(PartialEq, Clone, Copy)]
 pub struct Sight {
+    /// Horizontal zero adjustment in hundredths of a milliradian, positive
+    /// moves the reticle right.
     pub x_zero: i16,
+    /// Vertical zero adjustment in hundredths of a milliradian, positive
+    /// moves the reticle down.
     pub y_zero: i16,
+    /// Range in metres at which `x_zero` and `y_zero` put the reticle on the
+    /// point of impact.
+    pub zero_range: u8,
     pub battery_power: u8,
-    pub range: u8
-}
\ No newline at end of file
+    pub range: u8,
+    pub hop_strength: u8,
+    /// Index into the built-in profiles followed by `user_ammo`.
+    pub ammo_index: u8,
+    pub user_ammo: [AmmoProfile; USER_AMMO_SLOTS],
+    /// Muzzle velocity in m/s measured on a chronograph for each profile,
+    /// overriding the nominal one. Zero when not measured.
+    pub chrono_velocity: [u8; AMMO_NAMES.len()],
+    pub holdover_table: HoldoverTable,
+    pub drop_source: DropSource,
+    pub drop_table: DropTable,
+    pub wind: Wind,
+    /// Shot angle from the accelerometer in degrees, positive uphill.
+    pub inclination_deg: i8,
+    /// Roll of the sight from the accelerometer in degrees, positive when
+    /// tilted to the right.
+    pub cant_deg: i8,
+    /// Cant beyond which the indicator turns red.
+    pub cant_threshold_deg: u8,
+    pub lead: Lead,
+    pub units: Units,
+    pub atmosphere: Atmosphere,
+    /// Show the time of flight on the HUD.
+    pub show_flight_time: bool,
+    /// Show the impact energy on the HUD.
+    pub show_energy: bool,
+    /// Whether rangefinder readings update `range`. The encoder can always
+    /// override the last reading.
+    pub rangefinder_enabled: bool,
+    /// Last distance reported by the rangefinder in metres.
+    pub measured_range: Option<u8>,
+    /// Index into `STADIA_TARGETS` of the target the ranging brackets frame.
+    pub stadia_target: u8,
+    pub reticle: Reticle,
+    pub ladder: LadderSpacing,
+    pub appearance: Appearance,
+    pub pixel_scale: PixelScale,
+    pub calibration_target: CalibrationTarget,
+    /// Smoothed ambient light level in percent, `None` until the sensor
+    /// has been read.
+    pub ambient_light: Option<u8>,
+    /// Brightness step the ambient light calls for.
+    pub ambient_brightness: Option<u8>,
+    /// Minimum brightness, a dim reticle and only the range on the HUD,
+    /// for night vision devices. It overrides the appearance without
+    /// changing it, so turning it off restores the display.
+    pub night_vision: bool,
+}
+
+impl Sight {
+    pub fn new() -> Self {
+        let hop_strength = 5;
+        let ammo_index = 1;
+        Self {
+            x_zero: 0,
+            y_zero: 0,
+            zero_range: 30,
+            battery_power: 15,
+            range: 33,
+            hop_strength,
+            ammo_index,
+            user_ammo: DEFAULT_USER_AMMO,
+            chrono_velocity: [0; AMMO_NAMES.len()],
+            holdover_table: HoldoverTable::new(
+                &BUILTIN_AMMO[ammo_index as usize],
+                hop_strength,
+                Atmosphere::STANDARD.density(),
+            ),
+            drop_source: DropSource::Model,
+            drop_table: DropTable::EMPTY,
+            wind: Wind::CALM,
+            inclination_deg: 0,
+            cant_deg: 0,
+            cant_threshold_deg: 3,
+            lead: Lead::OFF,
+            units: Units::METRIC,
+            atmosphere: Atmosphere::STANDARD,
+            rangefinder_enabled: true,
+            measured_range: None,
+            stadia_target: 0,
+            reticle: Reticle::DEFAULT,
+            ladder: LadderSpacing::DEFAULT,
+            appearance: Appearance::DEFAULT,
+            pixel_scale: PixelScale::DEFAULT,
+            calibration_target: CalibrationTarget {
+                spacing_cm: 10,
+                distance_m: 10,
+            },
+            ambient_light: None,
+            ambient_brightness: None,
+            night_vision: false,
+            show_flight_time: false,
+            show_energy: false,
+        }
+    }
+
+    /// Offset of the zeroed reticle from the display centre in pixels,
+    /// `(right, down)`, including the fraction of a pixel.
+    pub fn zero_offset(&self) -> (Fixed, Fixed) {
+        (
+            self.pixel_scale.offset(self.x_zero),
+            self.pixel_scale.offset(self.y_zero),
+        )
+    }
+
+    /// The currently selected ammo profile, with the chronograph velocity
+    /// if one was measured.
+    pub fn ammo(&self) -> AmmoProfile {
+        let slot = self.ammo_slot();
+        let mut ammo = match slot.checked_sub(BUILTIN_AMMO.len()) {
+            None => BUILTIN_AMMO[slot],
+            Some(user) => self.user_ammo[user],
+        };
+        match self.chrono_velocity[slot] {
+            0 => {}
+            velocity => ammo.muzzle_velocity = Fixed::from(velocity),
+        }
+        ammo
+    }
+
+    /// Stores a chronograph reading for the selected profile, zero goes back
+    /// to the nominal velocity. Readings above `MAX_CHRONO_VELOCITY` are
+    /// ignored.
+    pub fn set_chrono_velocity(&mut self, velocity: u8) {
+        if velocity <= MAX_CHRONO_VELOCITY {
+            let slot = self.ammo_slot();
+            self.chrono_velocity[slot] = velocity;
+        }
+    }
+
+    /// Stores an ambient light reading and the brightness step it calls for.
+    pub fn set_ambient_light(&mut self, light: u8) {
+        let current = self
+            .ambient_brightness
+            .unwrap_or(self.appearance.brightness);
+        self.ambient_light = Some(light);
+        self.ambient_brightness = Some(self.appearance.light_curve.brightness_from(light, current));
+    }
+
+    /// Brightness step the panel should run at.
+    pub fn brightness(&self) -> u8 {
+        if self.night_vision {
+            return NIGHT_VISION_BRIGHTNESS;
+        }
+        match (self.appearance.brightness_source, self.ambient_brightness) {
+            (BrightnessSource::Sensor, Some(brightness)) => brightness,
+            _ => self.appearance.brightness,
+        }
+    }
+
+    pub fn reticle_color(&self) -> ReticleColor {
+        if self.night_vision {
+            return ReticleColor::NightVision;
+        }
+        match self.appearance.dark_color {
+            Some(color)
+                if self.appearance.brightness_source == BrightnessSource::Sensor
+                    && self.brightness() == MIN_BRIGHTNESS =>
+            {
+                color
+            }
+            _ => self.appearance.reticle_color,
+        }
+    }
+
+    /// The optional HUD readouts as bits, the time of flight first and the
+    /// impact energy second.
+    pub fn readouts(&self) -> u8 {
+        self.show_flight_time as u8 | (self.show_energy as u8) << 1
+    }
+
+    pub fn set_readouts(&mut self, readouts: u8) {
+        self.show_flight_time = readouts & 1 != 0;
+        self.show_energy = readouts & 2 != 0;
+    }
+
+    /// `ammo_index` kept within the profiles there are, for indexing the
+    /// per profile settings.
+    pub fn ammo_slot(&self) -> usize {
+        (self.ammo_index as usize).min(AMMO_NAMES.len() - 1)
+    }
+
+    /// Mutable access to the selected profile, `None` for built-in ones.
+    pub fn user_ammo_mut(&mut self) -> Option<&mut AmmoProfile> {
+        let index = self.ammo_slot().checked_sub(BUILTIN_AMMO.len())?;
+        self.user_ammo.get_mut(index)
+    }
+
+    /// Recomputes the holdover table if the ballistic inputs changed.
+    pub fn refresh_holdover(&mut self) {
+        let ammo = self.ammo();
+        let air_density = self.atmosphere.density();
+        if !self
+            .holdover_table
+            .is_for(&ammo, self.hop_strength, air_density)
+        {
+            self.holdover_table = HoldoverTable::new(&ammo, self.hop_strength, air_density);
+        }
+    }
+
+    /// Vertical holdover at the current range and inclination, relative to
+    /// the zero range so the zeroed reticle is on at `zero_range`. `None`
+    /// when the BB does not get to the range or the zero range.
+    pub fn holdover(&self) -> Option<Holdover> {
+        self.holdover_at(self.range)
+    }
+
+    /// Like `holdover`, at `range` metres instead of the current range.
+    pub fn holdover_at(&self, range: u8) -> Option<Holdover> {
+        let at_range = self.level_holdover(range)?;
+        let at_range = incline::compensate_level(at_range, range, self.inclination_deg);
+        // The bore keeps the angle it was zeroed at whatever the inclination.
+        let at_zero = self.level_holdover(self.zero_range)?;
+        Some(Holdover::from_mrad_tenths(
+            at_range.mrad_tenths.saturating_sub(at_zero.mrad_tenths),
+        ))
+    }
+
+    /// Holdover for a level shot with the bore parallel to the line of sight.
+    fn level_holdover(&self, range: u8) -> Option<Holdover> {
+        match self.drop_source {
+            DropSource::Model => self.holdover_table.holdover(range),
+            DropSource::Measured => Some(self.drop_table.holdover(range)),
+        }
+    }
+
+    /// Hash marks of the BDC ladder, every `ladder.step` metres out to
+    /// `ladder.max` or as far as the BB gets.
+    pub fn ladder_marks(&self) -> impl Iterator<Item = LadderMark> + '_ {
+        let step = self.ladder.step.max(1);
+        (1..=self.ladder.max / step).map_while(move |index| {
+            let range = index * step;
+            Some(LadderMark {
+                label: self.units.to_display(Quantity::Range, range as i16).round() as i16,
+                offset_px: self.holdover_at(range)?.pixels(self.pixel_scale),
+            })
+        })
+    }
+
+    /// Horizontal offset of the lead mark from the main aim point, where the
+    /// BB lands relative to the moving target, `None` when lead mode is off
+    /// or the BB does not get to the range.
+    pub fn lead_offset(&self) -> Option<Holdover> {
+        if !self.lead.enabled {
+            return None;
+        }
+        Some(self.lead.offset(self.range, self.flight_time_ms()?))
+    }
+
+    /// Horizontal wind correction at the current range, `None` when the BB
+    /// does not get to it.
+    pub fn windage(&self) -> Option<Holdover> {
+        Some(self.wind.windage(
+            self.range,
+            self.flight_time_ms()?,
+            self.ammo().muzzle_velocity,
+        ))
+    }
+
+    /// Time of flight to the current range in milliseconds, `None` when the
+    /// BB does not get to it.
+    pub fn flight_time_ms(&self) -> Option<i16> {
+        self.holdover_table.flight_time_ms(self.range)
+    }
+
+    /// Kinetic energy of the BB at the current range in joules, `None` when
+    /// the BB does not get to it.
+    pub fn impact_energy(&self) -> Option<Fixed> {
+        // In hundreds of m/s so the square stays within Q16.16.
+        let velocity = self.holdover_table.velocity(self.range)? / 100;
+        Some(velocity * velocity * self.ammo().mass_g * 5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::appearance::MAX_BRIGHTNESS;
+
+    #[test]
+    fn selects_built_in_then_user_profiles() {
+        let mut sight = Sight::new();
+        sight.user_ammo[0].mass_g = Fixed::from_ratio(28, 100);
+        sight.user_ammo[1].mass_g = Fixed::from_ratio(30, 100);
+        let last_builtin = BUILTIN_AMMO.len() as u8 - 1;
+        sight.ammo_index = last_builtin;
+        assert!(sight.ammo() == BUILTIN_AMMO[2]);
+        assert!(sight.user_ammo_mut().is_none());
+        sight.ammo_index = last_builtin + 1;
+        assert!(sight.ammo() == sight.user_ammo[0]);
+        assert!(sight.user_ammo_mut().is_some());
+        sight.ammo_index = last_builtin + 2;
+        assert!(sight.ammo() == sight.user_ammo[1]);
+    }
+
+    #[test]
+    fn out_of_range_profile_falls_back_to_the_last() {
+        let mut sight = Sight::new();
+        sight.ammo_index = u8::MAX;
+        assert!(sight.ammo() == sight.user_ammo[USER_AMMO_SLOTS - 1]);
+        sight.set_chrono_velocity(90);
+        assert_eq!(sight.chrono_velocity[AMMO_NAMES.len() - 1], 90);
+    }
+
+    #[test]
+    fn refreshes_the_holdover_for_the_selected_ammo() {
+        let mut sight = Sight::new();
+        let before = sight.holdover_at(50);
+        sight.ammo_index = 0;
+        sight.refresh_holdover();
+        assert!(sight.holdover_table.is_for(
+            &BUILTIN_AMMO[0],
+            sight.hop_strength,
+            sight.atmosphere.density()
+        ));
+        assert!(sight.holdover_at(50) != before);
+    }
+
+    #[test]
+    fn uses_the_measured_drops() {
+        let mut sight = Sight::new();
+        sight.drop_source = DropSource::Measured;
+        assert_eq!(sight.holdover_at(50), Some(Holdover::NONE));
+    }
+
+    #[test]
+    fn holdover_is_relative_to_the_zero_range() {
+        let mut sight = Sight::new();
+        sight.range = sight.zero_range;
+        assert_eq!(sight.holdover(), Some(Holdover::NONE));
+        let near = sight.holdover_at(10).unwrap();
+        let far = sight.holdover_at(50).unwrap();
+        assert!(near.mrad_tenths < 0 && far.mrad_tenths > 0);
+    }
+
+    #[test]
+    fn ladder_marks_follow_the_holdovers() {
+        let sight = Sight::new();
+        let marks: Vec<_> = sight.ladder_marks().collect();
+        assert!(!marks.is_empty());
+        for (index, mark) in marks.iter().enumerate() {
+            let range = (index as u8 + 1) * sight.ladder.step;
+            assert_eq!(mark.label, range as i16);
+            let holdover = sight.holdover_at(range).unwrap();
+            assert_eq!(mark.offset_px, holdover.pixels(sight.pixel_scale));
+        }
+    }
+
+    #[test]
+    fn sensor_brightness_switches_to_the_dark_colour() {
+        let mut sight = Sight::new();
+        sight.appearance.brightness_source = BrightnessSource::Sensor;
+        sight.appearance.dark_color = Some(ReticleColor::Green);
+        sight.appearance.reticle_color = ReticleColor::Red;
+        sight.set_ambient_light(100);
+        assert_eq!(sight.brightness(), MAX_BRIGHTNESS);
+        assert!(sight.reticle_color() == ReticleColor::Red);
+        sight.set_ambient_light(0);
+        assert_eq!(sight.brightness(), MIN_BRIGHTNESS);
+        assert!(sight.reticle_color() == ReticleColor::Green);
+        sight.night_vision = true;
+        assert_eq!(sight.brightness(), NIGHT_VISION_BRIGHTNESS);
+        assert!(sight.reticle_color() == ReticleColor::NightVision);
+    }
+
+    #[test]
+    fn energy_is_blank_where_the_time_of_flight_is() {
+        let mut sight = Sight::new();
+        sight.range = 30;
+        assert!(sight.flight_time_ms().is_some());
+        assert!(sight.impact_energy().unwrap() > Fixed::ZERO);
+        sight.range = 90;
+        assert_eq!(sight.flight_time_ms(), None);
+        assert_eq!(sight.impact_energy(), None);
+    }
+}
@@ -0,0 +1,317 @@
+use core::{convert::Infallible, fmt::Debug};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, OriginDimensions, Pixel, Point, RgbColor, Size},
+};
+
+use crate::fixed::Fixed;
+
+/// Widest display the row buffers can hold.
+const MAX_WIDTH: usize = 128;
+const ROW_BYTES: usize = MAX_WIDTH / 8;
+/// Rows of the area around the anchor kept while looking for the shape, one
+/// column per bit. Shapes that fit in it are drawn from it instead of being
+/// drawn again for every row.
+const CACHE_ROWS: usize = 24;
+const CACHE_COLUMNS: i32 = u32::BITS as i32;
+
+/// Draws a single colour shape at a fractional position, spreading each of
+/// its pixels over the four pixels around where it lands with intensities
+/// weighted by the distance. The aim point can then move in steps finer than
+/// a pixel.
+///
+/// `shape` draws onto the target it is given with its anchor at the point it
+/// is given, the whole pixel part of `position`. Small shapes are drawn once,
+/// larger ones once for each row they cover plus one, as there is no memory
+/// for the whole shape, so it has to draw the same pixels every time.
+pub fn draw<T, F>(target: &mut T, position: (Fixed, Fixed), color: Rgb565, shape: F)
+where
+    T: DrawTarget<Color = Rgb565, Error: Debug>,
+    F: Fn(&mut RowProbe, Point),
+{
+    let size = target.bounding_box().size;
+    let width = (size.width as i32).min(MAX_WIDTH as i32);
+    let anchor = Point::new(position.0.floor(), position.1.floor());
+    let (right, down) = (fraction(position.0), fraction(position.1));
+    let (left, up) = (Fixed::ONE - right, Fixed::ONE - down);
+    // Share of a shape pixel landing on itself, on the pixel to its right,
+    // below it and diagonally.
+    let weights = [left * up, right * up, left * down, right * down];
+
+    let window = anchor - Point::new(CACHE_COLUMNS / 2, CACHE_ROWS as i32 / 2);
+    let mut outline = RowProbe::new(size, None, window);
+    shape(&mut outline, anchor);
+    let Some((top_left, bottom_right)) = outline.bounds else {
+        return;
+    };
+    let cached = outline.window_holds(top_left, bottom_right);
+    // Pixels spill one to the right and one down.
+    let last_column = (bottom_right.x + 1).min(width - 1);
+    let last_row = (bottom_right.y + 1).min(size.height as i32 - 1);
+    let mut above = [0; ROW_BYTES];
+    for y in top_left.y..=last_row {
+        let row = if y > bottom_right.y {
+            [0; ROW_BYTES]
+        } else if cached {
+            outline.cached_row(y)
+        } else {
+            let mut probe = RowProbe::new(size, Some(y), window);
+            shape(&mut probe, anchor);
+            probe.bits
+        };
+        let pixels = (top_left.x..=last_column).filter_map(|x| {
+            let sources = [
+                is_set(&row, x),
+                is_set(&row, x - 1),
+                is_set(&above, x),
+                is_set(&above, x - 1),
+            ];
+            if !sources.contains(&true) {
+                return None;
+            }
+            let intensity = sources
+                .into_iter()
+                .zip(weights)
+                .filter(|(set, _)| *set)
+                .fold(Fixed::ZERO, |sum, (_, weight)| sum + weight);
+            let shaded = scale(color, intensity);
+            (shaded != Rgb565::BLACK).then_some(Pixel(Point::new(x, y), shaded))
+        });
+        target.draw_iter(pixels).unwrap();
+        above = row;
+    }
+}
+
+/// Fractional part of `value`, from zero up to one.
+fn fraction(value: Fixed) -> Fixed {
+    Fixed::from_raw(value.raw() & (Fixed::ONE.raw() - 1))
+}
+
+fn is_set(row: &[u8; ROW_BYTES], x: i32) -> bool {
+    (0..MAX_WIDTH as i32).contains(&x) && row[x as usize / 8] & (1 << (x % 8)) != 0
+}
+
+/// `color` at `intensity`, from zero for black up to one for unchanged.
+fn scale(color: Rgb565, intensity: Fixed) -> Rgb565 {
+    let channel = |value: u8| (Fixed::from(value as i16) * intensity).round() as u8;
+    Rgb565::new(channel(color.r()), channel(color.g()), channel(color.b()))
+}
+
+/// Records where a shape draws instead of showing it. It notes the area the
+/// shape covers, and which pixels it sets in `row` if one is given or in
+/// the window around the anchor if not.
+pub struct RowProbe {
+    size: Size,
+    row: Option<i32>,
+    bits: [u8; ROW_BYTES],
+    /// Top left and bottom right pixels drawn on.
+    bounds: Option<(Point, Point)>,
+    /// Top left corner of `cache`.
+    window: Point,
+    cache: [u32; CACHE_ROWS],
+}
+
+impl RowProbe {
+    fn new(size: Size, row: Option<i32>, window: Point) -> Self {
+        Self {
+            size,
+            row,
+            bits: [0; ROW_BYTES],
+            bounds: None,
+            window,
+            cache: [0; CACHE_ROWS],
+        }
+    }
+
+    /// Whether the pixels between the corners are all in the window.
+    fn window_holds(&self, top_left: Point, bottom_right: Point) -> bool {
+        let offset = top_left - self.window;
+        let end = bottom_right - self.window;
+        offset.x >= 0 && offset.y >= 0 && end.x < CACHE_COLUMNS && end.y < CACHE_ROWS as i32
+    }
+
+    /// Row `y` of the window as display columns.
+    fn cached_row(&self, y: i32) -> [u8; ROW_BYTES] {
+        let mut bits = [0; ROW_BYTES];
+        let cached = self.cache[(y - self.window.y) as usize];
+        for column in (0..CACHE_COLUMNS).filter(|column| cached & 1 << column != 0) {
+            let x = (self.window.x + column) as usize;
+            bits[x / 8] |= 1 << (x % 8);
+        }
+        bits
+    }
+}
+
+impl OriginDimensions for RowProbe {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for RowProbe {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let width = (self.size.width as i32).min(MAX_WIDTH as i32);
+        for Pixel(point, _) in pixels {
+            if !(0..width).contains(&point.x) || !(0..self.size.height as i32).contains(&point.y) {
+                continue;
+            }
+            self.bounds = Some(match self.bounds {
+                None => (point, point),
+                Some((top_left, bottom_right)) => (
+                    Point::new(top_left.x.min(point.x), top_left.y.min(point.y)),
+                    Point::new(bottom_right.x.max(point.x), bottom_right.y.max(point.y)),
+                ),
+            });
+            match self.row {
+                Some(row) if row == point.y => {
+                    self.bits[point.x as usize / 8] |= 1 << (point.x % 8);
+                }
+                Some(_) => {}
+                None => {
+                    let offset = point - self.window;
+                    if (0..CACHE_COLUMNS).contains(&offset.x)
+                        && (0..CACHE_ROWS as i32).contains(&offset.y)
+                    {
+                        self.cache[offset.y as usize] |= 1 << offset.x;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reticle::{CustomReticle, Reticle, ReticleKind, ReticleParams};
+
+    const WIDTH: usize = 128;
+    const HEIGHT: usize = 96;
+
+    /// A display in memory.
+    struct Canvas([[Rgb565; WIDTH]; HEIGHT]);
+
+    impl Canvas {
+        fn new() -> Self {
+            Canvas([[Rgb565::BLACK; WIDTH]; HEIGHT])
+        }
+
+        /// Centre of the red channel, weighted by its intensity.
+        fn centroid(&self) -> (f64, f64) {
+            let (mut sum, mut x_sum, mut y_sum) = (0.0, 0.0, 0.0);
+            for (y, row) in self.0.iter().enumerate() {
+                for (x, pixel) in row.iter().enumerate() {
+                    let intensity = pixel.r() as f64;
+                    sum += intensity;
+                    x_sum += x as f64 * intensity;
+                    y_sum += y as f64 * intensity;
+                }
+            }
+            (x_sum / sum, y_sum / sum)
+        }
+    }
+
+    impl OriginDimensions for Canvas {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+
+    impl DrawTarget for Canvas {
+        type Color = Rgb565;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(point, color) in pixels {
+                if (0..WIDTH as i32).contains(&point.x) && (0..HEIGHT as i32).contains(&point.y) {
+                    self.0[point.y as usize][point.x as usize] = color;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn reticle(kind: ReticleKind, size: u8) -> Reticle {
+        let mut reticle = Reticle {
+            kind,
+            ..Reticle::DEFAULT
+        };
+        *reticle.selected_mut() = ReticleParams { size, thickness: 1 };
+        reticle
+    }
+
+    fn render(reticle: &Reticle, position: (Fixed, Fixed)) -> Canvas {
+        let mut canvas = Canvas::new();
+        draw(&mut canvas, position, Rgb565::RED, |probe, anchor| {
+            reticle.draw(probe, anchor, Rgb565::RED, &CustomReticle::EMPTY)
+        });
+        canvas
+    }
+
+    /// Small enough to be drawn from the cache, and too large for it.
+    fn reticles() -> [Reticle; 6] {
+        [
+            reticle(ReticleKind::Dot, 3),
+            reticle(ReticleKind::Crosshair, 8),
+            reticle(ReticleKind::CircleDot, 12),
+            reticle(ReticleKind::Dot, 30),
+            reticle(ReticleKind::Crosshair, 30),
+            reticle(ReticleKind::CircleDot, 40),
+        ]
+    }
+
+    #[test]
+    fn whole_pixel_positions_draw_the_shape_as_is() {
+        for reticle in reticles() {
+            let center = Point::new(60, 40);
+            let mut expected = Canvas::new();
+            reticle.draw(&mut expected, center, Rgb565::RED, &CustomReticle::EMPTY);
+            let drawn = render(&reticle, (Fixed::from_int(60), Fixed::from_int(40)));
+            assert!(drawn.0 == expected.0);
+        }
+    }
+
+    #[test]
+    fn centroid_follows_fractional_positions() {
+        let quarters = [0, 1, 2, 3].map(|quarter| Fixed::from_ratio(quarter, 4));
+        for reticle in reticles() {
+            let (x, y) = render(&reticle, (Fixed::from_int(60), Fixed::from_int(40))).centroid();
+            for right in quarters {
+                for down in quarters {
+                    let position = (Fixed::from_int(60) + right, Fixed::from_int(40) + down);
+                    let (moved_x, moved_y) = render(&reticle, position).centroid();
+                    let expected_x = x + right.raw() as f64 / Fixed::ONE.raw() as f64;
+                    let expected_y = y + down.raw() as f64 / Fixed::ONE.raw() as f64;
+                    assert!(
+                        (moved_x - expected_x).abs() < 0.02 && (moved_y - expected_y).abs() < 0.02,
+                        "({moved_x}, {moved_y}) instead of ({expected_x}, {expected_y})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clips_at_the_edges() {
+        for reticle in reticles() {
+            for position in [(-5, -5), (125, 93), (0, 50), (127, 95)] {
+                let position = (
+                    Fixed::from_int(position.0 as i16) + Fixed::HALF,
+                    Fixed::from_int(position.1 as i16) + Fixed::HALF,
+                );
+                render(&reticle, position);
+            }
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::fixed::Fixed;
+
+const YARDS_PER_METRE: Fixed = Fixed::from_f32(1.093_613);
+const MOA_PER_MRAD: Fixed = Fixed::from_f32(3.437_747);
+const FPS_PER_MPS: Fixed = Fixed::from_f32(3.280_84);
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum RangeUnit {
+    Metres,
+    Yards,
+}
+
+/// Unit of the zero adjustment clicks.
+#[derive(PartialEq, Clone, Copy)]
+pub enum AngleUnit {
+    /// 0.1 milliradian clicks.
+    Mrad,
+    /// Quarter minute of angle clicks.
+    Moa,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum VelocityUnit {
+    MetresPerSecond,
+    FeetPerSecond,
+}
+
+/// What a value stands for, which decides how it is converted for display.
+/// Values are always stored in the base unit of their quantity.
+#[derive(Clone, Copy)]
+pub enum Quantity {
+    /// Shown as is.
+    Plain,
+    /// Stored in metres.
+    Range,
+    /// Stored in hundredths of a milliradian, adjusted in clicks.
+    Angle,
+    /// Stored in m/s.
+    Velocity,
+    /// Stored and shown in centimetres whatever the units, for impacts
+    /// measured on a metric target.
+    Centimetres,
+    /// Stored and shown in km/h whatever the units.
+    KilometresPerHour,
+}
+
+/// Units the user wants values shown in.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Units {
+    pub range: RangeUnit,
+    pub angle: AngleUnit,
+    pub velocity: VelocityUnit,
+}
+
+/// Bytes `Units::to_bytes` produces.
+pub const UNITS_BYTES: usize = 1;
+
+impl Units {
+    pub const METRIC: Units = Units {
+        range: RangeUnit::Metres,
+        angle: AngleUnit::Mrad,
+        velocity: VelocityUnit::MetresPerSecond,
+    };
+
+    /// Converts `value` from the base unit to the display unit, snapped to
+    /// whole steps.
+    pub fn to_display(self, quantity: Quantity, value: i16) -> Fixed {
+        let value = Fixed::from(value);
+        let converted = match quantity {
+            Quantity::Plain | Quantity::Centimetres | Quantity::KilometresPerHour => value,
+            Quantity::Range => match self.range {
+                RangeUnit::Metres => value,
+                RangeUnit::Yards => value * YARDS_PER_METRE,
+            },
+            Quantity::Angle => match self.angle {
+                AngleUnit::Mrad => value / 100,
+                AngleUnit::Moa => value / 100 * MOA_PER_MRAD,
+            },
+            Quantity::Velocity => match self.velocity {
+                VelocityUnit::MetresPerSecond => value,
+                VelocityUnit::FeetPerSecond => value * FPS_PER_MPS,
+            },
+        };
+        let step = self.step(quantity);
+        step * (converted / step).round()
+    }
+
+    /// Converts a value in the display unit back to the base unit.
+    pub fn to_base(self, quantity: Quantity, value: Fixed) -> i16 {
+        let base = match quantity {
+            Quantity::Plain | Quantity::Centimetres | Quantity::KilometresPerHour => value,
+            Quantity::Range => match self.range {
+                RangeUnit::Metres => value,
+                RangeUnit::Yards => value / YARDS_PER_METRE,
+            },
+            Quantity::Angle => match self.angle {
+                AngleUnit::Mrad => value * 100,
+                AngleUnit::Moa => value / MOA_PER_MRAD * 100,
+            },
+            Quantity::Velocity => match self.velocity {
+                VelocityUnit::MetresPerSecond => value,
+                VelocityUnit::FeetPerSecond => value / FPS_PER_MPS,
+            },
+        };
+        base.round().clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+
+    /// Smallest adjustment in the display unit, one click for angles.
+    pub fn step(self, quantity: Quantity) -> Fixed {
+        match (quantity, self.angle) {
+            (Quantity::Angle, AngleUnit::Mrad) => Fixed::from_ratio(1, 10),
+            (Quantity::Angle, AngleUnit::Moa) => Fixed::from_ratio(1, 4),
+            _ => Fixed::ONE,
+        }
+    }
+
+    /// Steps `value` by `clicks` display steps, returning the new value in
+    /// the base unit. Keeps stepping while the base value does not change,
+    /// so display units finer than the base unit still move.
+    pub fn step_by(self, quantity: Quantity, value: i16, clicks: i8) -> i16 {
+        let step = self.step(quantity) * clicks as i32;
+        let mut display = self.to_display(quantity, value);
+        loop {
+            display += step;
+            let stepped = self.to_base(quantity, display);
+            if stepped != value || display == Fixed::MAX || display == Fixed::MIN {
+                return stepped;
+            }
+        }
+    }
+
+    pub fn suffix(self, quantity: Quantity) -> &'static str {
+        match quantity {
+            Quantity::Plain => "",
+            Quantity::Range => match self.range {
+                RangeUnit::Metres => "m",
+                RangeUnit::Yards => "yd",
+            },
+            Quantity::Angle => match self.angle {
+                AngleUnit::Mrad => "mil",
+                AngleUnit::Moa => "moa",
+            },
+            Quantity::Velocity => match self.velocity {
+                VelocityUnit::MetresPerSecond => "m/s",
+                VelocityUnit::FeetPerSecond => "fps",
+            },
+            Quantity::Centimetres => "cm",
+            Quantity::KilometresPerHour => "km/h",
+        }
+    }
+
+    /// One bit per quantity, set for the imperial unit, as stored in the
+    /// EEPROM.
+    pub fn to_bytes(self) -> [u8; UNITS_BYTES] {
+        [self.range as u8 | (self.angle as u8) << 1 | (self.velocity as u8) << 2]
+    }
+
+    /// Units stored by `to_bytes`, if no other bits are set.
+    pub fn from_bytes(bytes: &[u8; UNITS_BYTES]) -> Option<Self> {
+        let [bits] = *bytes;
+        let imperial = |bit: u8| bits & 1 << bit != 0;
+        (bits < 1 << 3).then_some(Units {
+            range: if imperial(0) {
+                RangeUnit::Yards
+            } else {
+                RangeUnit::Metres
+            },
+            angle: if imperial(1) {
+                AngleUnit::Moa
+            } else {
+                AngleUnit::Mrad
+            },
+            velocity: if imperial(2) {
+                VelocityUnit::FeetPerSecond
+            } else {
+                VelocityUnit::MetresPerSecond
+            },
+        })
+    }
+
+    /// Writes `value` converted to the display unit right-aligned into
+    /// `buf`, followed by the unit suffix.
+    pub fn format(self, quantity: Quantity, value: i16, buf: &mut [u8]) {
+        let decimals = match (quantity, self.angle) {
+            (Quantity::Angle, AngleUnit::Mrad) => 1,
+            (Quantity::Angle, AngleUnit::Moa) => 2,
+            _ => 0,
+        };
+        let suffix = self.suffix(quantity).as_bytes();
+        let (number, unit) = buf.split_at_mut(buf.len() - suffix.len());
+        self.to_display(quantity, value).format(decimals, number);
+        unit.copy_from_slice(suffix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMPERIAL: Units = Units {
+        range: RangeUnit::Yards,
+        angle: AngleUnit::Moa,
+        velocity: VelocityUnit::FeetPerSecond,
+    };
+
+    const QUANTITIES: [Quantity; 6] = [
+        Quantity::Plain,
+        Quantity::Range,
+        Quantity::Angle,
+        Quantity::Velocity,
+        Quantity::Centimetres,
+        Quantity::KilometresPerHour,
+    ];
+
+    #[test]
+    fn round_trips_values_finer_than_a_step() {
+        for units in [Units::METRIC, IMPERIAL] {
+            for quantity in [Quantity::Plain, Quantity::Range, Quantity::Velocity] {
+                for value in 0..=300 {
+                    let display = units.to_display(quantity, value);
+                    assert_eq!(units.to_base(quantity, display), value);
+                }
+            }
+        }
+        assert_eq!(
+            IMPERIAL.to_display(Quantity::Range, 50),
+            Fixed::from_int(55)
+        );
+        assert_eq!(
+            IMPERIAL.to_display(Quantity::Velocity, 10),
+            Fixed::from_int(33)
+        );
+    }
+
+    #[test]
+    fn round_trips_angles_to_the_nearest_click() {
+        // A click is 10 hundredths of a milliradian, or a quarter minute of
+        // angle, 7.27 of them.
+        for (units, half_click) in [(Units::METRIC, 5), (IMPERIAL, 4)] {
+            for value in 0..=300 {
+                let display = units.to_display(Quantity::Angle, value);
+                let back = units.to_base(Quantity::Angle, display);
+                assert!(
+                    (back - value).abs() <= half_click,
+                    "{value} came back as {back}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn step_by_moves_and_steps_back() {
+        for units in [Units::METRIC, IMPERIAL] {
+            for quantity in QUANTITIES {
+                for value in 1..=200 {
+                    // Start on a whole step, as the menus do.
+                    let value = units.to_base(quantity, units.to_display(quantity, value));
+                    let up = units.step_by(quantity, value, 1);
+                    assert!(up > value, "{value} stepped up to {up}");
+                    assert_eq!(units.step_by(quantity, up, -1), value, "{value}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn step_by_steps_one_click_of_angle() {
+        assert_eq!(Units::METRIC.step_by(Quantity::Angle, 120, 1), 130);
+        assert_eq!(Units::METRIC.step_by(Quantity::Angle, 120, -2), 100);
+        let up = IMPERIAL.step_by(Quantity::Angle, 120, 1);
+        assert!((up - 120 - 7).abs() <= 4, "{up}");
+    }
+
+    #[test]
+    fn formats_with_the_unit() {
+        let mut buffer = *b"XXXXXX";
+        Units::METRIC.format(Quantity::Velocity, 10, &mut buffer);
+        assert_eq!(&buffer, b" 10m/s");
+        IMPERIAL.format(Quantity::Velocity, 10, &mut buffer);
+        assert_eq!(&buffer, b" 33fps");
+        Units::METRIC.format(Quantity::Angle, 120, &mut buffer);
+        assert_eq!(&buffer, b"1.2mil");
+        IMPERIAL.format(Quantity::Centimetres, -12, &mut buffer);
+        assert_eq!(&buffer, b" -12cm");
+    }
+}